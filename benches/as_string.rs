@@ -0,0 +1,72 @@
+use std::borrow::Cow;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use serde_json::from_str;
+
+use serde_this_or_that::*;
+
+/// `MsgStd` - uses the standard `Deserialize` implementation for `String`
+#[derive(Clone, Debug, Deserialize)]
+pub struct MsgStd {
+    pub name: String,
+    pub city: String,
+    pub country: String,
+}
+
+/// `MsgOwned` - uses the `as_string` helper, which always allocates
+#[derive(Clone, Debug, Deserialize)]
+pub struct MsgOwned {
+    #[serde(deserialize_with = "as_string")]
+    pub name: String,
+    #[serde(deserialize_with = "as_string")]
+    pub city: String,
+    #[serde(deserialize_with = "as_string")]
+    pub country: String,
+}
+
+/// `MsgCow` - uses the `as_cow_str` helper, which borrows where possible
+#[derive(Clone, Debug, Deserialize)]
+pub struct MsgCow<'a> {
+    #[serde(borrow, deserialize_with = "as_cow_str")]
+    pub name: Cow<'a, str>,
+    #[serde(borrow, deserialize_with = "as_cow_str")]
+    pub city: Cow<'a, str>,
+    #[serde(borrow, deserialize_with = "as_cow_str")]
+    pub country: Cow<'a, str>,
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let data = r#"
+    {
+        "name": "Lorem ipsum dolor sit amet, consectetur adipiscing elit",
+        "city": "Sed do eiusmod tempor incididunt ut labore",
+        "country": "Ut enim ad minim veniam, quis nostrud exercitation"
+    }"#;
+
+    c.bench_function("de: std String  (input: str)", |b| {
+        b.iter(|| from_str::<MsgStd>(black_box(data)).unwrap())
+    });
+    c.bench_function("de: as_string   (input: str)", |b| {
+        b.iter(|| from_str::<MsgOwned>(black_box(data)).unwrap())
+    });
+    c.bench_function("de: as_cow_str  (input: str)", |b| {
+        b.iter(|| from_str::<MsgCow<'_>>(black_box(data)).unwrap())
+    });
+
+    let data = r#"
+    {
+        "name": 1234567890,
+        "city": -42,
+        "country": true
+    }"#;
+
+    c.bench_function("de: as_string   (input: number)", |b| {
+        b.iter(|| from_str::<MsgOwned>(black_box(data)).unwrap())
+    });
+    c.bench_function("de: as_cow_str  (input: number)", |b| {
+        b.iter(|| from_str::<MsgCow<'_>>(black_box(data)).unwrap())
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
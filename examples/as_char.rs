@@ -0,0 +1,87 @@
+#![deny(warnings)]
+#![warn(rust_2018_idioms)]
+
+#[macro_use]
+extern crate log;
+
+use serde::Deserialize;
+use serde_this_or_that::as_char;
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Msg {
+    #[serde(deserialize_with = "as_char")]
+    pub status_flag: char,
+}
+
+// A simple type alias so as to DRY.
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+fn main() -> Result<()> {
+    sensible_env_logger::init!();
+
+    trace!("With Empty String:");
+    let data = r#"
+    {
+        "status_flag": ""
+    }"#;
+
+    let m: Msg = serde_json::from_str(data).unwrap();
+    assert_eq!(m.status_flag, '\0');
+    trace!("  {m:?}");
+
+    trace!("With Null:  ");
+    let data = r#"
+    {
+        "status_flag": null
+    }"#;
+
+    let m: Msg = serde_json::from_str(data).unwrap();
+    assert_eq!(m.status_flag, '\0');
+    trace!("  {m:?}");
+
+    trace!("With String:");
+    let data = r#"
+    {
+        "status_flag": "Y"
+    }"#;
+
+    let m: Msg = serde_json::from_str(data).unwrap();
+
+    trace!("  {m:?}");
+    assert_eq!(m.status_flag, 'Y');
+
+    trace!("With Padded String:");
+    let data = r#"
+    {
+        "status_flag": " N  "
+    }"#;
+
+    let m: Msg = serde_json::from_str(data).unwrap();
+
+    trace!("  {m:?}");
+    assert_eq!(m.status_flag, 'N');
+
+    trace!("With U64 (Code Point):");
+    let data = r#"
+    {
+        "status_flag": 89
+    }"#;
+
+    let m: Msg = serde_json::from_str(data).unwrap();
+
+    trace!("  {m:?}");
+    assert_eq!(m.status_flag, 'Y');
+
+    trace!("With Multi-Character String:");
+    let data = r#"
+    {
+        "status_flag": "YES"
+    }"#;
+
+    let r = serde_json::from_str::<Msg>(data);
+
+    trace!("  {r:?}");
+    assert!(r.is_err());
+
+    Ok(())
+}
@@ -0,0 +1,421 @@
+//! Plain functions which apply the crate's coercion rules, without `serde`.
+//!
+//! These are the rules used by the helper functions (such as [`as_bool`] and
+//! [`as_u64`]), so values from other sources, such as command-line
+//! arguments, HTTP headers, or text columns in a database, can be coerced in
+//! the same way.
+//!
+//! ```rust
+//! use serde_this_or_that::coerce::{self, CoerceError};
+//!
+//! assert_eq!(coerce::str_to_bool("Yes"), Ok(true));
+//! assert_eq!(coerce::str_to_u64("2.5"), Ok(3));
+//! assert_eq!(coerce::f64_to_i64(-2.5), -3);
+//! assert_eq!(coerce::str_to_f64(""), Err(CoerceError::Empty));
+//! assert_eq!(coerce::str_to_i64("N/A"), Err(CoerceError::Invalid));
+//! ```
+//!
+//! An empty string is reported as [`CoerceError::Empty`], as the helper
+//! functions treat it as a *default* value rather than as an error.
+//!
+//! [`as_bool`]: crate::as_bool
+//! [`as_u64`]: crate::as_u64
+
+use core::fmt;
+
+/// The "truthy" phrases, which are matched *case-insensitively*.
+pub(crate) const TRUTHY_PHRASES: &[&str] = &["OK", "ON", "TRUE", "Y", "YES"];
+
+/// The "falsy" phrases, which are matched *case-insensitively*.
+pub(crate) const FALSY_PHRASES: &[&str] = &["NG", "OFF", "FALSE", "N", "NO"];
+
+/// An error which occurred while coercing a value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CoerceError {
+    /// The string is empty.
+    Empty,
+    /// The value is not one which can be coerced to the type, such as `"abc"`
+    /// for a number, or `"maybe"` for a boolean.
+    Invalid,
+    /// The value is a number, but is out of range for the type, such as a
+    /// negative integer for [`i64_to_u64`], or `2` for a boolean.
+    ///
+    /// Note that a float, or a string which is parsed as one, saturates
+    /// instead: [`str_to_u64`] returns `0` for `"-7"` or `"-2.5"`, as does
+    /// [`f64_to_u64`] for `-2.5`.
+    OutOfRange,
+}
+
+impl fmt::Display for CoerceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CoerceError::Empty => "empty string",
+            CoerceError::Invalid => "invalid value",
+            CoerceError::OutOfRange => "value out of range",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CoerceError {}
+
+/// Returns true if `v` is equal to any of the (ASCII) `phrases`, ignoring
+/// case. Unlike `to_uppercase()`, this does not allocate.
+#[inline]
+fn eq_any_ignore_case(v: &str, phrases: &[&str]) -> bool {
+    phrases.iter().any(|p| v.eq_ignore_ascii_case(p))
+}
+
+/// Coerces a string to a boolean, as [`as_bool`] does.
+///
+/// The following strings are matched *case-insensitively*:
+///
+///   - `1`, `OK`, `ON`, `T`, `TRUE`, `Y`, and `YES` result in `true`.
+///   - `0`, `NG`, `OFF`, `F`, `FALSE`, `N`, and `NO` result in `false`.
+///
+/// # Errors
+/// Returns [`CoerceError::Empty`] for an empty string, and
+/// [`CoerceError::Invalid`] for any other string.
+///
+/// [`as_bool`]: crate::as_bool
+#[inline]
+pub fn str_to_bool(v: &str) -> Result<bool, CoerceError> {
+    // First, try to match common true/false phrases *exactly*.
+    // This approach is likely more efficient.
+    match v {
+        "t" | "T" | "true" | "True" | "1" => Ok(true),
+        "f" | "F" | "false" | "False" | "0" => Ok(false),
+        // So from the above, we've already matched the following
+        // "truthy" phrases: ["T", "1"]
+        // and the following "falsy" phrases: ["F", "0"].
+        // To be completely thorough, we also need to do a case-
+        // insensitive match on ["OK", "ON", "TRUE", "Y", "YES"]
+        // and its counterpart, ["NG", "OFF", "FALSE", "N", "NO"].
+        other if eq_any_ignore_case(other, TRUTHY_PHRASES) => Ok(true),
+        other if eq_any_ignore_case(other, FALSY_PHRASES) => Ok(false),
+        "" => Err(CoerceError::Empty),
+        _ => Err(CoerceError::Invalid),
+    }
+}
+
+/// Coerces a *zero* or a *one* to a boolean.
+///
+/// # Errors
+/// Returns [`CoerceError::OutOfRange`] for any other number.
+#[inline]
+pub fn u64_to_bool(v: u64) -> Result<bool, CoerceError> {
+    match v {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(CoerceError::OutOfRange),
+    }
+}
+
+/// Coerces a float to a boolean, after truncating it towards zero, so `0.5`
+/// results in `false` and `1.5` in `true`.
+///
+/// # Errors
+/// Returns [`CoerceError::OutOfRange`] if the truncated value is not a *zero*
+/// or a *one*.
+#[inline]
+pub fn f64_to_bool(v: f64) -> Result<bool, CoerceError> {
+    match v as u8 {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(CoerceError::OutOfRange),
+    }
+}
+
+/// Coerces a string to an *unsigned* value, as [`as_u64`] does.
+///
+/// A string which is not an integer, such as `"1.5"` or `"1e3"`, is parsed
+/// as a float and then rounded with [`f64_to_u64`].
+///
+/// # Errors
+/// Returns [`CoerceError::Empty`] for an empty string, and
+/// [`CoerceError::Invalid`] if the string is not a valid numeric value.
+///
+/// [`as_u64`]: crate::as_u64
+#[inline]
+pub fn str_to_u64(v: &str) -> Result<u64, CoerceError> {
+    if let Ok(n) = v.parse::<u64>() {
+        Ok(n)
+    } else if v.is_empty() {
+        Err(CoerceError::Empty)
+    } else if let Ok(f) = v.parse::<f64>() {
+        Ok(f64_to_u64(f))
+    } else {
+        Err(CoerceError::Invalid)
+    }
+}
+
+/// Coerces a string to a *signed* value, as [`as_i64`] does.
+///
+/// A string which is not an integer, such as `"1.5"` or `"1e3"`, is parsed
+/// as a float and then rounded with [`f64_to_i64`].
+///
+/// # Errors
+/// Returns [`CoerceError::Empty`] for an empty string, and
+/// [`CoerceError::Invalid`] if the string is not a valid numeric value.
+///
+/// [`as_i64`]: crate::as_i64
+#[inline]
+pub fn str_to_i64(v: &str) -> Result<i64, CoerceError> {
+    if let Ok(n) = v.parse::<i64>() {
+        Ok(n)
+    } else if v.is_empty() {
+        Err(CoerceError::Empty)
+    } else if let Ok(f) = v.parse::<f64>() {
+        Ok(f64_to_i64(f))
+    } else {
+        Err(CoerceError::Invalid)
+    }
+}
+
+/// Coerces a string to a *float* value, as [`as_f64`] does.
+///
+/// # Errors
+/// Returns [`CoerceError::Empty`] for an empty string, and
+/// [`CoerceError::Invalid`] if the string is not a valid numeric value.
+///
+/// [`as_f64`]: crate::as_f64
+#[inline]
+pub fn str_to_f64(v: &str) -> Result<f64, CoerceError> {
+    match v.parse::<f64>() {
+        Ok(f) => Ok(f),
+        Err(_) if v.is_empty() => Err(CoerceError::Empty),
+        Err(_) => Err(CoerceError::Invalid),
+    }
+}
+
+/// Coerces a float to an *unsigned* value, rounding half away from zero, as
+/// `v.round() as u64` does (but without needing `std`).
+///
+/// Like the cast, this saturates for values which are out of range (so that
+/// a negative value results in zero), and results in zero for `NaN`.
+#[inline]
+pub fn f64_to_u64(v: f64) -> u64 {
+    // The cast truncates towards zero, and saturates for out-of-range values
+    // (or returns zero for `NaN`).
+    let n = v as u64;
+    let frac = v - n as f64;
+    if frac >= 0.5 {
+        n.saturating_add(1)
+    } else if frac <= -0.5 {
+        n.saturating_sub(1)
+    } else {
+        n
+    }
+}
+
+/// Coerces a float to a *signed* value, rounding half away from zero, as
+/// `v.round() as i64` does (but without needing `std`).
+///
+/// Like the cast, this saturates for values which are out of range, and
+/// results in zero for `NaN`.
+#[inline]
+pub fn f64_to_i64(v: f64) -> i64 {
+    let n = v as i64;
+    let frac = v - n as f64;
+    if frac >= 0.5 {
+        n.saturating_add(1)
+    } else if frac <= -0.5 {
+        n.saturating_sub(1)
+    } else {
+        n
+    }
+}
+
+/// Coerces a *signed* value to an *unsigned* one.
+///
+/// # Errors
+/// Returns [`CoerceError::OutOfRange`] for a negative number.
+#[inline]
+pub fn i64_to_u64(v: i64) -> Result<u64, CoerceError> {
+    u64::try_from(v).map_err(|_| CoerceError::OutOfRange)
+}
+
+/// Coerces an *unsigned* value to a *signed* one.
+///
+/// # Errors
+/// Returns [`CoerceError::OutOfRange`] if the value overflows an `i64`.
+#[inline]
+pub fn u64_to_i64(v: u64) -> Result<i64, CoerceError> {
+    i64::try_from(v).map_err(|_| CoerceError::OutOfRange)
+}
+
+/// Coerces a string to a character, as [`as_char`] does.
+///
+/// The string must contain exactly one character, either as-is or after
+/// trimming any leading and trailing whitespace; see [`str_to_char_exact`]
+/// to not trim it.
+///
+/// # Errors
+/// Returns [`CoerceError::Empty`] for an empty string, and
+/// [`CoerceError::Invalid`] for any other string.
+///
+/// [`as_char`]: crate::as_char
+#[inline]
+pub fn str_to_char(v: &str) -> Result<char, CoerceError> {
+    match str_to_char_exact(v) {
+        Err(CoerceError::Invalid) => match str_to_char_exact(v.trim()) {
+            Ok(c) => Ok(c),
+            Err(_) => Err(CoerceError::Invalid),
+        },
+        result => result,
+    }
+}
+
+/// Coerces a string to a character, as [`as_char_with`] does with
+/// [`CharMode::Exact`]: the string must contain exactly one character, as-is.
+///
+/// # Errors
+/// Returns [`CoerceError::Empty`] for an empty string, and
+/// [`CoerceError::Invalid`] for any other string.
+///
+/// [`as_char_with`]: crate::as_char_with
+/// [`CharMode::Exact`]: crate::CharMode::Exact
+#[inline]
+pub fn str_to_char_exact(v: &str) -> Result<char, CoerceError> {
+    let mut chars = v.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        (None, _) => Err(CoerceError::Empty),
+        _ => Err(CoerceError::Invalid),
+    }
+}
+
+/// Coerces a Unicode *code point* to a character, so `89` results in `'Y'`.
+///
+/// # Errors
+/// Returns [`CoerceError::OutOfRange`] if the value is not a valid Unicode
+/// scalar value.
+#[inline]
+pub fn u64_to_char(v: u64) -> Result<char, CoerceError> {
+    u32::try_from(v)
+        .ok()
+        .and_then(char::from_u32)
+        .ok_or(CoerceError::OutOfRange)
+}
+
+/// Coerces a Unicode *code point* to a character, as [`u64_to_char`] does.
+///
+/// # Errors
+/// Returns [`CoerceError::OutOfRange`] if the value is not a valid Unicode
+/// scalar value.
+#[inline]
+pub fn i64_to_char(v: i64) -> Result<char, CoerceError> {
+    u32::try_from(v)
+        .ok()
+        .and_then(char::from_u32)
+        .ok_or(CoerceError::OutOfRange)
+}
+
+/// Returns true if the number written in `v` is exactly `n`, so that a
+/// string such as `"1.0"` is reported as lossless, and `"1.5"` as lossy.
+#[cfg(feature = "observe")]
+pub(crate) fn is_exact(v: &str, n: f64) -> bool {
+    v.parse::<f64>() == Ok(n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_str_to_bool() {
+        for v in ["1", "t", "True", "ok", "On", "y", "YES"] {
+            assert_eq!(str_to_bool(v), Ok(true), "{v}");
+        }
+        for v in ["0", "f", "False", "ng", "Off", "n", "NO"] {
+            assert_eq!(str_to_bool(v), Ok(false), "{v}");
+        }
+        assert_eq!(str_to_bool(""), Err(CoerceError::Empty));
+        assert_eq!(str_to_bool("maybe"), Err(CoerceError::Invalid));
+        assert_eq!(str_to_bool(" yes"), Err(CoerceError::Invalid));
+    }
+
+    #[test]
+    fn test_number_to_bool() {
+        assert_eq!(u64_to_bool(0), Ok(false));
+        assert_eq!(u64_to_bool(1), Ok(true));
+        assert_eq!(u64_to_bool(2), Err(CoerceError::OutOfRange));
+        assert_eq!(f64_to_bool(0.9), Ok(false));
+        assert_eq!(f64_to_bool(1.5), Ok(true));
+        assert_eq!(f64_to_bool(2.0), Err(CoerceError::OutOfRange));
+    }
+
+    #[test]
+    fn test_str_to_number() {
+        assert_eq!(str_to_u64("42"), Ok(42));
+        assert_eq!(str_to_u64("2.5"), Ok(3));
+        assert_eq!(str_to_u64("1e3"), Ok(1000));
+        assert_eq!(str_to_u64("-7"), Ok(0));
+        assert_eq!(str_to_u64(""), Err(CoerceError::Empty));
+        assert_eq!(str_to_u64(" 7"), Err(CoerceError::Invalid));
+
+        assert_eq!(str_to_i64("-42"), Ok(-42));
+        assert_eq!(str_to_i64("-2.5"), Ok(-3));
+        assert_eq!(str_to_i64(""), Err(CoerceError::Empty));
+        assert_eq!(str_to_i64("A+"), Err(CoerceError::Invalid));
+
+        assert_eq!(str_to_f64("81"), Ok(81.0));
+        assert_eq!(str_to_f64("-1.25"), Ok(-1.25));
+        assert_eq!(str_to_f64(""), Err(CoerceError::Empty));
+        assert_eq!(str_to_f64("1,5"), Err(CoerceError::Invalid));
+    }
+
+    #[test]
+    fn test_integer_conversions() {
+        assert_eq!(i64_to_u64(7), Ok(7));
+        assert_eq!(i64_to_u64(-1), Err(CoerceError::OutOfRange));
+        assert_eq!(u64_to_i64(7), Ok(7));
+        assert_eq!(u64_to_i64(u64::MAX), Err(CoerceError::OutOfRange));
+    }
+
+    #[test]
+    fn test_char() {
+        assert_eq!(str_to_char("Y"), Ok('Y'));
+        assert_eq!(str_to_char(" Y "), Ok('Y'));
+        assert_eq!(str_to_char(" "), Ok(' '));
+        assert_eq!(str_to_char(""), Err(CoerceError::Empty));
+        assert_eq!(str_to_char("  "), Err(CoerceError::Invalid));
+        assert_eq!(str_to_char("YN"), Err(CoerceError::Invalid));
+        assert_eq!(str_to_char_exact(" "), Ok(' '));
+        assert_eq!(str_to_char_exact(" Y "), Err(CoerceError::Invalid));
+        assert_eq!(u64_to_char(89), Ok('Y'));
+        assert_eq!(i64_to_char(-1), Err(CoerceError::OutOfRange));
+        assert_eq!(u64_to_char(0xD800), Err(CoerceError::OutOfRange));
+    }
+
+    #[test]
+    fn test_rounding_matches_std() {
+        let values = [
+            0.0,
+            -0.0,
+            0.49999999999999994,
+            0.5,
+            1.5,
+            2.5,
+            -0.5,
+            -0.7,
+            -1.5,
+            123.45,
+            -123.45,
+            4503599627370497.0,
+            9007199254740993.0,
+            1e19,
+            -1e19,
+            1e300,
+            f64::MAX,
+            f64::MIN,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NAN,
+        ];
+        for v in values {
+            assert_eq!(f64_to_u64(v), v.round() as u64, "{v}");
+            assert_eq!(f64_to_i64(v), v.round() as i64, "{v}");
+        }
+    }
+}
@@ -0,0 +1,683 @@
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::Deref;
+
+use crate::coerce;
+use crate::de::{self, Deserialize, Deserializer, SeqAccess};
+use crate::de_coerce::Coerce;
+use crate::lenient::Lenient;
+use crate::ser::{Serialize, SerializeTuple, Serializer};
+use crate::typed::{is_typed, is_typed_serializer};
+
+/// The shape a [`Coerced`] value had in the original data.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Repr {
+    /// A value which is written as-is, such as one created with
+    /// [`Coerced::from`].
+    #[default]
+    Native,
+    /// A boolean, such as `true`.
+    Bool,
+    /// A signed integer, such as `-3`.
+    I64,
+    /// An unsigned integer, such as `3`.
+    U64,
+    /// A float, such as `2.5`.
+    F64,
+    /// A non-empty string, such as `"3"` or `"true"`.
+    Str,
+    /// A `null` value.
+    Null,
+    /// An empty string, `""`.
+    EmptyStr,
+}
+
+/// A value which is de-serialized with the same rules as the crate's helper
+/// functions (for example, [`as_u64`](crate::as_u64) for a `Coerced<u64>`),
+/// and which remembers the [`Repr`] it had in the original data.
+///
+/// When serialized, the value is written back in the same shape: a value
+/// which was originally a string is written as a string, a number as a
+/// number of the same kind (such as `1` rather than `1.0` for a
+/// `Coerced<f64>`), and a `null` or an empty string is written back as-is.
+/// This allows a struct to be round-tripped without changing how its fields
+/// are represented. A value which no longer fits its original shape, such
+/// as `2.5` for a field which was `1`, is written as-is instead.
+///
+/// ```rust
+/// use serde::{Deserialize, Serialize};
+/// use serde_this_or_that::{Coerced, Repr};
+///
+/// #[derive(Deserialize, Serialize)]
+/// #[serde(rename_all = "camelCase")]
+/// struct Msg {
+///     num_attempts: Coerced<u64>,
+///     max_attempts: Coerced<u64>,
+/// }
+///
+/// let json = r#"{"numAttempts":"3","maxAttempts":5}"#;
+/// let m: Msg = serde_json::from_str(json).unwrap();
+///
+/// assert_eq!(*m.num_attempts, 3);
+/// assert_eq!(m.num_attempts.repr(), Repr::Str);
+/// assert_eq!(serde_json::to_string(&m).unwrap(), json);
+/// ```
+///
+/// With the `typed` feature, for a format which is not human-readable, such
+/// as `bincode`, the value is written in its native form, followed by its
+/// [`Repr`].
+///
+/// `Coerced<T>` is supported for any type which implements [`Coerce`](crate::Coerce).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Coerced<T> {
+    value: T,
+    repr: Repr,
+}
+
+impl<T> Coerced<T> {
+    /// Returns a `Coerced` value with the given `repr`.
+    ///
+    /// Note that a [`Repr::Null`] or [`Repr::EmptyStr`] is serialized as a
+    /// `null` or `""`, regardless of the `value`.
+    pub const fn with_repr(value: T, repr: Repr) -> Self {
+        Self { value, repr }
+    }
+
+    /// Returns the shape the value had in the original data.
+    pub const fn repr(&self) -> Repr {
+        self.repr
+    }
+
+    /// Returns the inner value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> From<T> for Coerced<T> {
+    /// Returns a `Coerced` value with a [`Repr::Native`] representation.
+    fn from(value: T) -> Self {
+        Self::with_repr(value, Repr::Native)
+    }
+}
+
+impl<T> Deref for Coerced<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> Serialize for Coerced<T>
+where
+    T: Serialize + fmt::Display,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if is_typed_serializer(&serializer) {
+            let mut tuple = serializer.serialize_tuple(2)?;
+            tuple.serialize_element(&self.value)?;
+            tuple.serialize_element(&self.repr.to_u8())?;
+            return tuple.end();
+        }
+
+        match self.repr {
+            Repr::Native => self.value.serialize(serializer),
+            Repr::Bool | Repr::I64 | Repr::U64 | Repr::F64 => {
+                self.value.serialize(ReprSerializer {
+                    inner: serializer,
+                    repr: self.repr,
+                })
+            }
+            Repr::Str => serializer.collect_str(&self.value),
+            Repr::Null => serializer.serialize_none(),
+            Repr::EmptyStr => serializer.serialize_str(""),
+        }
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Coerced<T>
+where
+    T: Coerce,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if is_typed(&deserializer) {
+            return deserializer.deserialize_tuple(2, NativeVisitor(PhantomData));
+        }
+
+        deserializer
+            .deserialize_any(ReprVisitor(T::Visitor::default()))
+            .map(|(value, repr)| Coerced::with_repr(value, repr))
+    }
+}
+
+impl Repr {
+    fn to_u8(self) -> u8 {
+        match self {
+            Repr::Native => 0,
+            Repr::Str => 1,
+            Repr::Null => 2,
+            Repr::EmptyStr => 3,
+            Repr::Bool => 4,
+            Repr::I64 => 5,
+            Repr::U64 => 6,
+            Repr::F64 => 7,
+        }
+    }
+
+    fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Repr::Native),
+            1 => Some(Repr::Str),
+            2 => Some(Repr::Null),
+            3 => Some(Repr::EmptyStr),
+            4 => Some(Repr::Bool),
+            5 => Some(Repr::I64),
+            6 => Some(Repr::U64),
+            7 => Some(Repr::F64),
+            _ => None,
+        }
+    }
+}
+
+/// A visitor for the value and [`Repr`] of a [`Coerced`] value, written by a
+/// format which is not human-readable.
+struct NativeVisitor<T>(PhantomData<T>);
+
+impl<'de, T> de::Visitor<'de> for NativeVisitor<T>
+where
+    T: Coerce,
+{
+    type Value = Coerced<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a value and its representation")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let Lenient(value) = seq
+            .next_element::<Lenient<T>>()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let repr = seq
+            .next_element::<u8>()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        let repr = Repr::from_u8(repr).ok_or_else(|| {
+            de::Error::invalid_value(
+                de::Unexpected::Unsigned(repr.into()),
+                &"a representation from 0 to 7",
+            )
+        })?;
+        Ok(Coerced::with_repr(value, repr))
+    }
+}
+
+/// A visitor which records the [`Repr`] of the input, and passes it on to
+/// the inner visitor.
+struct ReprVisitor<V>(V);
+
+impl<'de, V> de::Visitor<'de> for ReprVisitor<V>
+where
+    V: de::Visitor<'de>,
+{
+    type Value = (V::Value, Repr);
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.expecting(formatter)
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_bool(v).map(|v| (v, Repr::Bool))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_i64(v).map(|v| (v, Repr::I64))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_u64(v).map(|v| (v, Repr::U64))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_f64(v).map(|v| (v, Repr::F64))
+    }
+
+    fn visit_char<E>(self, v: char) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_char(v).map(|v| (v, Repr::Native))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let repr = str_repr(v);
+        self.0.visit_str(v).map(|v| (v, repr))
+    }
+
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let repr = str_repr(v);
+        self.0.visit_borrowed_str(v).map(|v| (v, repr))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let repr = str_repr(&v);
+        self.0.visit_string(v).map(|v| (v, repr))
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_unit().map(|v| (v, Repr::Null))
+    }
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_unit().map(|v| (v, Repr::Null))
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        crate::de_wrapped::visit_wrapped_map(map, self)
+    }
+}
+
+#[inline]
+fn str_repr(v: &str) -> Repr {
+    if v.is_empty() {
+        Repr::EmptyStr
+    } else {
+        Repr::Str
+    }
+}
+
+/// A [`Serializer`] which writes a boolean or number in the shape of a
+/// [`Repr`], such as `1` rather than `true` for a [`Repr::U64`].
+///
+/// A value which cannot be written in that shape without losing information
+/// (such as `2.5` for a [`Repr::U64`]), or which is not a scalar, is passed
+/// on to the inner serializer as-is.
+struct ReprSerializer<S> {
+    inner: S,
+    repr: Repr,
+}
+
+/// Defines `serialize_*` methods which widen the value to the given type.
+macro_rules! serialize_widened {
+    ($($method:ident($ty:ty) => $wide:ident;)*) => {
+        $(
+            fn $method(self, v: $ty) -> Result<Self::Ok, Self::Error> {
+                self.$wide(v.into())
+            }
+        )*
+    };
+}
+
+/// Defines `serialize_*` methods which pass the value on as-is.
+macro_rules! serialize_as_is {
+    ($($method:ident($ty:ty);)*) => {
+        $(
+            fn $method(self, v: $ty) -> Result<Self::Ok, Self::Error> {
+                self.inner.$method(v)
+            }
+        )*
+    };
+}
+
+impl<S> Serializer for ReprSerializer<S>
+where
+    S: Serializer,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = S::SerializeSeq;
+    type SerializeTuple = S::SerializeTuple;
+    type SerializeTupleStruct = S::SerializeTupleStruct;
+    type SerializeTupleVariant = S::SerializeTupleVariant;
+    type SerializeMap = S::SerializeMap;
+    type SerializeStruct = S::SerializeStruct;
+    type SerializeStructVariant = S::SerializeStructVariant;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+        match self.repr {
+            Repr::I64 => self.inner.serialize_i64(v.into()),
+            Repr::U64 => self.inner.serialize_u64(v.into()),
+            Repr::F64 => self.inner.serialize_f64(u8::from(v).into()),
+            _ => self.inner.serialize_bool(v),
+        }
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+        match self.repr {
+            Repr::Bool | Repr::U64 => match coerce::i64_to_u64(v) {
+                Ok(n) => self.serialize_u64(n),
+                Err(_) => self.inner.serialize_i64(v),
+            },
+            Repr::F64 if v as f64 as i64 == v => self.inner.serialize_f64(v as f64),
+            _ => self.inner.serialize_i64(v),
+        }
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+        match self.repr {
+            Repr::Bool => match coerce::u64_to_bool(v) {
+                Ok(b) => self.inner.serialize_bool(b),
+                Err(_) => self.inner.serialize_u64(v),
+            },
+            Repr::I64 => match coerce::u64_to_i64(v) {
+                Ok(n) => self.inner.serialize_i64(n),
+                Err(_) => self.inner.serialize_u64(v),
+            },
+            Repr::F64 if v as f64 as u64 == v => self.inner.serialize_f64(v as f64),
+            _ => self.inner.serialize_u64(v),
+        }
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+        match self.repr {
+            Repr::Bool if v == 0.0 || v == 1.0 => self.inner.serialize_bool(v == 1.0),
+            Repr::I64 if coerce::f64_to_i64(v) as f64 == v => {
+                self.inner.serialize_i64(coerce::f64_to_i64(v))
+            }
+            Repr::U64 if coerce::f64_to_u64(v) as f64 == v => {
+                self.inner.serialize_u64(coerce::f64_to_u64(v))
+            }
+            _ => self.inner.serialize_f64(v),
+        }
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        match self.repr {
+            Repr::Bool => match coerce::str_to_bool(v) {
+                Ok(b) => self.inner.serialize_bool(b),
+                Err(_) => self.inner.serialize_str(v),
+            },
+            Repr::I64 => match v.parse() {
+                Ok(n) => self.inner.serialize_i64(n),
+                Err(_) => self.inner.serialize_str(v),
+            },
+            Repr::U64 => match v.parse() {
+                Ok(n) => self.inner.serialize_u64(n),
+                Err(_) => self.inner.serialize_str(v),
+            },
+            Repr::F64 => match v.parse::<f64>() {
+                Ok(f) if f.is_finite() => self.inner.serialize_f64(f),
+                _ => self.inner.serialize_str(v),
+            },
+            _ => self.inner.serialize_str(v),
+        }
+    }
+
+    serialize_widened! {
+        serialize_i8(i8) => serialize_i64;
+        serialize_i16(i16) => serialize_i64;
+        serialize_i32(i32) => serialize_i64;
+        serialize_u8(u8) => serialize_u64;
+        serialize_u16(u16) => serialize_u64;
+        serialize_u32(u32) => serialize_u64;
+        serialize_f32(f32) => serialize_f64;
+    }
+
+    serialize_as_is! {
+        serialize_i128(i128);
+        serialize_u128(u128);
+        serialize_char(char);
+        serialize_bytes(&[u8]);
+        serialize_unit_struct(&'static str);
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        self.inner.serialize_none()
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.inner.serialize_some(value)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        self.inner.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.inner
+            .serialize_unit_variant(name, variant_index, variant)
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.inner.serialize_newtype_struct(name, value)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.inner
+            .serialize_newtype_variant(name, variant_index, variant, value)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        self.inner.serialize_seq(len)
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.inner.serialize_tuple(len)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.inner.serialize_tuple_struct(name, len)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        self.inner
+            .serialize_tuple_variant(name, variant_index, variant, len)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        self.inner.serialize_map(len)
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        self.inner.serialize_struct(name, len)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        self.inner
+            .serialize_struct_variant(name, variant_index, variant, len)
+    }
+
+    fn collect_str<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + fmt::Display,
+    {
+        self.inner.collect_str(value)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    struct TestCoercedStruct {
+        count: Coerced<u64>,
+        grade: Coerced<f64>,
+        active: Coerced<bool>,
+        name: Coerced<String>,
+    }
+
+    #[test]
+    fn test_coerced_remembers_repr() {
+        let json = r#"{"count":"3","grade":null,"active":true,"name":""}"#;
+        let deserialized: TestCoercedStruct = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            deserialized,
+            TestCoercedStruct {
+                count: Coerced::with_repr(3, Repr::Str),
+                grade: Coerced::with_repr(0.0, Repr::Null),
+                active: Coerced::with_repr(true, Repr::Bool),
+                name: Coerced::with_repr(String::new(), Repr::EmptyStr),
+            }
+        );
+    }
+
+    #[test]
+    fn test_coerced_round_trip() {
+        for json in [
+            r#"{"count":"3","grade":null,"active":true,"name":""}"#,
+            r#"{"count":3,"grade":"81.5","active":"true","name":"x"}"#,
+            r#"{"count":null,"grade":"","active":null,"name":null}"#,
+        ] {
+            let deserialized: TestCoercedStruct = serde_json::from_str(json).unwrap();
+            assert_eq!(serde_json::to_string(&deserialized).unwrap(), json);
+        }
+    }
+
+    #[test]
+    fn test_coerced_keeps_native_kind() {
+        for json in [
+            r#"{"count":2.0,"grade":1,"active":1,"name":7}"#,
+            r#"{"count":3,"grade":-2,"active":0.0,"name":false}"#,
+            r#"{"count":"3","grade":0,"active":true,"name":-1.5}"#,
+        ] {
+            let deserialized: TestCoercedStruct = serde_json::from_str(json).unwrap();
+            assert_eq!(serde_json::to_string(&deserialized).unwrap(), json);
+        }
+    }
+
+    #[test]
+    fn test_coerced_normalizes_strings() {
+        // The shape is preserved, but the string is written in its
+        // normalized form.
+        let json = r#"{"count":"2.5","grade":"1.50","active":"Yes","name":"x"}"#;
+        let deserialized: TestCoercedStruct = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            serde_json::to_string(&deserialized).unwrap(),
+            r#"{"count":"3","grade":"1.5","active":"true","name":"x"}"#
+        );
+    }
+
+    #[test]
+    fn test_coerced_does_not_fit_native_kind() {
+        // A value which changed is written as-is, rather than losing
+        // information.
+        let c = Coerced::with_repr(2.5, Repr::U64);
+        assert_eq!(serde_json::to_string(&c).unwrap(), "2.5");
+
+        let c = Coerced::with_repr(2_u64, Repr::Bool);
+        assert_eq!(serde_json::to_string(&c).unwrap(), "2");
+
+        let c = Coerced::with_repr(String::from("x"), Repr::F64);
+        assert_eq!(serde_json::to_string(&c).unwrap(), r#""x""#);
+    }
+
+    #[test]
+    fn test_coerced_with_invalid_value() {
+        let json = r#"{"count":"x","grade":1,"active":true,"name":""}"#;
+        let deserialized = serde_json::from_str::<TestCoercedStruct>(json);
+        assert!(deserialized.is_err());
+    }
+
+    #[test]
+    fn test_coerced_from_value() {
+        let c = Coerced::from('x');
+        assert_eq!(*c, 'x');
+        assert_eq!(c.repr(), Repr::Native);
+        assert_eq!(c.into_inner(), 'x');
+    }
+}
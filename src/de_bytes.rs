@@ -0,0 +1,608 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::de::{self, Deserialize, Deserializer, IgnoredAny, SeqAccess, Unexpected, Visitor};
+use crate::typed::is_typed;
+
+/// A textual encoding of binary data, as tried by [`as_bytes_in_order`]
+/// and [`as_opt_bytes_in_order`] when the input is a string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ByteEncoding {
+    /// Hexadecimal, with two (case-insensitive) digits per byte.
+    Hex,
+    /// Base64 with the *standard* alphabet (`+` and `/`); padding is optional.
+    Base64,
+    /// Base64 with the *URL-safe* alphabet (`-` and `_`); padding is optional.
+    Base64Url,
+}
+
+/// The order in which string encodings are tried by [`as_bytes`] and
+/// [`as_opt_bytes`].
+///
+/// Hex comes first, since it is the strictest of the three: a string such as
+/// `"cafe"` is valid in every encoding, and is most likely meant as hex.
+pub const DEFAULT_BYTE_ENCODINGS: &[ByteEncoding] = &[
+    ByteEncoding::Hex,
+    ByteEncoding::Base64,
+    ByteEncoding::Base64Url,
+];
+
+/// De-serialize either a `null`, byte buffer, `str`, or an array of
+/// small integers as a *byte* vector.
+///
+/// Native byte buffers (as found in formats such as MessagePack or CBOR)
+/// are taken as-is, and an array must only contain integers from `0`
+/// to `255`.
+///
+/// A string is decoded by trying each of the [`DEFAULT_BYTE_ENCODINGS`]
+/// in turn; see [`as_bytes_in_order`] to customize this.
+///
+/// # Errors
+/// Returns an error if a string is non-empty and not valid in any of the
+/// encodings, or if an array contains a value that is not a byte.
+///
+/// # Returns
+/// The byte (`Vec<u8>`) value of a byte buffer, string, or array. A `null`
+/// value or an empty string results in an empty vector.
+///
+pub fn as_bytes<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    as_bytes_in_order(deserializer, DEFAULT_BYTE_ENCODINGS)
+}
+
+/// Same as [`as_bytes`], but tries the given string `encodings` in order.
+///
+/// To use this with `deserialize_with`, wrap it in a function of your own:
+///
+/// ```rust
+/// use serde::{Deserialize, Deserializer};
+/// use serde_this_or_that::{as_bytes_in_order, ByteEncoding};
+///
+/// fn as_base64_or_hex<'de, D>(deserializer: D) -> Result<Vec<u8>, D::Error>
+/// where
+///     D: Deserializer<'de>,
+/// {
+///     as_bytes_in_order(deserializer, &[ByteEncoding::Base64, ByteEncoding::Hex])
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Msg {
+///     #[serde(deserialize_with = "as_base64_or_hex")]
+///     payload: Vec<u8>,
+/// }
+///
+/// let m: Msg = serde_json::from_str(r#"{"payload": "cafe"}"#).unwrap();
+/// assert_eq!(m.payload, [0x71, 0xa7, 0xde]);
+/// ```
+///
+pub fn as_bytes_in_order<'de, D>(
+    deserializer: D,
+    encodings: &[ByteEncoding],
+) -> Result<Vec<u8>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if is_typed(&deserializer) {
+        deserializer.deserialize_byte_buf(DeserializeBytesWithVisitor { encodings })
+    } else {
+        deserializer.deserialize_any(DeserializeBytesWithVisitor { encodings })
+    }
+}
+
+/// De-serialize either a byte buffer, `str`, or an array of small integers
+/// as a *byte* vector wrapped in [`Some`],
+/// and a `null` value as [`None`].
+///
+/// A string is decoded by trying each of the [`DEFAULT_BYTE_ENCODINGS`]
+/// in turn; see [`as_opt_bytes_in_order`] to customize this.
+///
+/// # Returns
+/// A [`Some`] with the byte (`Vec<u8>`) value of a byte buffer, string,
+/// or array.
+///
+/// A [`None`] in the case of:
+///   * a `bool` or number value.
+///   * a `null` value, or an empty string.
+///   * any *de-serialization* errors.
+///     * ex. a string is not valid in any of the encodings.
+///     * ex. an array contains a value that is not a byte.
+///
+pub fn as_opt_bytes<'de, D>(deserializer: D) -> Result<Option<Vec<u8>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    as_opt_bytes_in_order(deserializer, DEFAULT_BYTE_ENCODINGS)
+}
+
+/// Same as [`as_opt_bytes`], but tries the given string `encodings` in order.
+///
+pub fn as_opt_bytes_in_order<'de, D>(
+    deserializer: D,
+    encodings: &[ByteEncoding],
+) -> Result<Option<Vec<u8>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if is_typed(&deserializer) {
+        deserializer.deserialize_option(DeserializeOptionalBytesWithVisitor { encodings })
+    } else {
+        deserializer.deserialize_any(DeserializeOptionalBytesWithVisitor { encodings })
+    }
+}
+
+impl ByteEncoding {
+    /// Decodes `v` with this encoding, returning `None` if it is not valid.
+    pub fn decode(self, v: &str) -> Option<Vec<u8>> {
+        match self {
+            ByteEncoding::Hex => decode_hex(v.as_bytes()),
+            ByteEncoding::Base64 => decode_base64(v.as_bytes(), b'+', b'/'),
+            ByteEncoding::Base64Url => decode_base64(v.as_bytes(), b'-', b'_'),
+        }
+    }
+}
+
+#[inline]
+fn str_to_bytes(v: &str, encodings: &[ByteEncoding]) -> Option<Vec<u8>> {
+    encodings.iter().find_map(|e| e.decode(v))
+}
+
+fn decode_hex(v: &[u8]) -> Option<Vec<u8>> {
+    fn nibble(c: u8) -> Option<u8> {
+        match c {
+            b'0'..=b'9' => Some(c - b'0'),
+            b'a'..=b'f' => Some(c - b'a' + 10),
+            b'A'..=b'F' => Some(c - b'A' + 10),
+            _ => None,
+        }
+    }
+
+    let pairs = v.chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return None;
+    }
+
+    pairs
+        .map(|pair| Some(nibble(pair[0])? << 4 | nibble(pair[1])?))
+        .collect()
+}
+
+fn decode_base64(v: &[u8], c62: u8, c63: u8) -> Option<Vec<u8>> {
+    let sextet = |c: u8| match c {
+        b'A'..=b'Z' => Some(c - b'A'),
+        b'a'..=b'z' => Some(c - b'a' + 26),
+        b'0'..=b'9' => Some(c - b'0' + 52),
+        _ if c == c62 => Some(62),
+        _ if c == c63 => Some(63),
+        _ => None,
+    };
+
+    // Padding is optional, but if present it must complete the final quad.
+    let data = match (v, v.len() % 4) {
+        ([rest @ .., b'=', b'='], 0) | ([rest @ .., b'='], 0) => rest,
+        _ => v,
+    };
+    if data.len() % 4 == 1 {
+        return None;
+    }
+
+    let mut out = Vec::with_capacity(data.len() * 3 / 4);
+    for quad in data.chunks(4) {
+        let mut acc: u32 = 0;
+        for &c in quad {
+            acc = acc << 6 | u32::from(sextet(c)?);
+        }
+        // Left-align the bits of a partial quad, then emit whole bytes only.
+        acc <<= 6 * (4 - quad.len() as u32);
+        let [_, b0, b1, b2] = acc.to_be_bytes();
+        out.extend_from_slice(&[b0, b1, b2][..quad.len() - 1]);
+    }
+
+    Some(out)
+}
+
+struct DeserializeBytesWithVisitor<'a> {
+    encodings: &'a [ByteEncoding],
+}
+
+impl<'de> Visitor<'de> for DeserializeBytesWithVisitor<'_> {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("bytes, an encoded string, or an array of integers")
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(v)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if v.is_empty() {
+            Ok(Vec::new())
+        } else if let Some(bytes) = str_to_bytes(v, self.encodings) {
+            Ok(bytes)
+        } else {
+            Err(E::invalid_value(Unexpected::Str(v), &self))
+        }
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut out = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(b) = seq.next_element::<u8>()? {
+            out.push(b);
+        }
+        Ok(out)
+    }
+
+    /// We encounter a `null` value; this default implementation returns an
+    /// "empty" vector.
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Vec::new())
+    }
+}
+
+struct DeserializeOptionalBytesWithVisitor<'a> {
+    encodings: &'a [ByteEncoding],
+}
+
+impl<'de> Visitor<'de> for DeserializeOptionalBytesWithVisitor<'_> {
+    type Value = Option<Vec<u8>>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("bytes, an encoded string, or an array of integers")
+    }
+
+    fn visit_bool<E>(self, _: bool) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(None)
+    }
+
+    fn visit_i64<E>(self, _: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(None)
+    }
+
+    fn visit_u64<E>(self, _: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(None)
+    }
+
+    fn visit_f64<E>(self, _: f64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(None)
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Some(v.to_vec()))
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Some(v))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if v.is_empty() {
+            Ok(None)
+        } else {
+            Ok(str_to_bytes(v, self.encodings))
+        }
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        // Keep consuming elements after an invalid one, so the rest of the
+        // input can still be de-serialized.
+        let mut out = Some(Vec::with_capacity(seq.size_hint().unwrap_or(0)));
+        while let Some(LenientByte(b)) = seq.next_element()? {
+            match (&mut out, b) {
+                (Some(bytes), Some(b)) => bytes.push(b),
+                _ => out = None,
+            }
+        }
+        Ok(out)
+    }
+
+    /// We encounter a `null` value; this default implementation returns an
+    /// `Option::None` value.
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(None)
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(None)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        as_bytes_in_order(deserializer, self.encodings).map(Some)
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        crate::de_wrapped::visit_wrapped_map(map, self)
+    }
+}
+
+/// An array element which is `None` if it is not a byte, rather than an error.
+struct LenientByte(Option<u8>);
+
+impl<'de> Deserialize<'de> for LenientByte {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct LenientByteVisitor;
+
+        impl<'de> Visitor<'de> for LenientByteVisitor {
+            type Value = Option<u8>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("any value")
+            }
+
+            fn visit_bool<E>(self, _: bool) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(None)
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(u8::try_from(v).ok())
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(u8::try_from(v).ok())
+            }
+
+            fn visit_f64<E>(self, _: f64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(None)
+            }
+
+            fn visit_str<E>(self, _: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(None)
+            }
+
+            fn visit_bytes<E>(self, _: &[u8]) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(None)
+            }
+
+            fn visit_unit<E>(self) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(None)
+            }
+
+            fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                IgnoredAny.visit_seq(seq).map(|_| None)
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::MapAccess<'de>,
+            {
+                Ok(crate::de_wrapped::visit_wrapped(&mut map, self)?.unwrap_or(None))
+            }
+        }
+
+        deserializer
+            .deserialize_any(LenientByteVisitor)
+            .map(LenientByte)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::de::value::{BytesDeserializer, Error};
+    use serde::Deserialize;
+
+    mod as_bytes_tests {
+        use super::*;
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct TestBytesStruct {
+            #[serde(deserialize_with = "as_bytes")]
+            field: Vec<u8>,
+        }
+
+        #[test]
+        fn test_as_bytes_with_native_bytes() {
+            let deserialized = as_bytes(BytesDeserializer::<Error>::new(b"\x00\xff")).unwrap();
+            assert_eq!(deserialized, [0x00, 0xff]);
+        }
+
+        #[test]
+        fn test_as_bytes_with_array() {
+            let json = r#"{"field": [0, 1, 255]}"#;
+            let deserialized: TestBytesStruct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized.field, [0, 1, 255]);
+        }
+
+        #[test]
+        fn test_as_bytes_with_hex() {
+            let json = r#"{"field": "DEADbeef"}"#;
+            let deserialized: TestBytesStruct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized.field, [0xde, 0xad, 0xbe, 0xef]);
+        }
+
+        #[test]
+        fn test_as_bytes_with_base64() {
+            for (encoded, expected) in [
+                ("aGVsbG8=", &b"hello"[..]),
+                ("aGVsbG8", b"hello"),
+                ("aGk=", b"hi"),
+                ("+/+/", &[0xfb, 0xff, 0xbf]),
+            ] {
+                let json = format!(r#"{{"field": "{}"}}"#, encoded);
+                let deserialized: TestBytesStruct = serde_json::from_str(&json).unwrap();
+                assert_eq!(deserialized.field, expected, "{encoded}");
+            }
+        }
+
+        #[test]
+        fn test_as_bytes_with_url_safe_base64() {
+            let json = r#"{"field": "-_-_"}"#;
+            let deserialized: TestBytesStruct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized.field, [0xfb, 0xff, 0xbf]);
+        }
+
+        #[test]
+        fn test_as_bytes_with_null_and_empty_string() {
+            for json in [r#"{"field": null}"#, r#"{"field": ""}"#] {
+                let deserialized: TestBytesStruct = serde_json::from_str(json).unwrap();
+                assert!(deserialized.field.is_empty());
+            }
+        }
+
+        #[test]
+        fn test_as_bytes_with_invalid_values() {
+            for json in [
+                r#"{"field": "not bytes!"}"#,
+                r#"{"field": "aGVsbG8=="}"#,
+                r#"{"field": [1, 256]}"#,
+                r#"{"field": 123}"#,
+            ] {
+                let deserialized = serde_json::from_str::<TestBytesStruct>(json);
+                assert!(deserialized.is_err(), "{json}");
+            }
+        }
+
+        #[test]
+        fn test_as_bytes_in_order() {
+            let base64_first = [ByteEncoding::Base64, ByteEncoding::Hex];
+            let mut de = serde_json::Deserializer::from_str(r#""cafe""#);
+            let deserialized = as_bytes_in_order(&mut de, &base64_first).unwrap();
+            assert_eq!(deserialized, [0x71, 0xa7, 0xde]);
+
+            let mut de = serde_json::Deserializer::from_str(r#""cafe""#);
+            let deserialized = as_bytes(&mut de).unwrap();
+            assert_eq!(deserialized, [0xca, 0xfe]);
+        }
+    }
+
+    mod as_opt_bytes_tests {
+        use super::*;
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct TestOptBytesStruct {
+            #[serde(deserialize_with = "as_opt_bytes")]
+            field: Option<Vec<u8>>,
+            #[serde(default)]
+            other: u64,
+        }
+
+        #[test]
+        fn test_as_opt_bytes_with_valid_values() {
+            for json in [
+                r#"{"field": [104, 105]}"#,
+                r#"{"field": "6869"}"#,
+                r#"{"field": "aGk"}"#,
+            ] {
+                let deserialized: TestOptBytesStruct = serde_json::from_str(json).unwrap();
+                assert_eq!(deserialized.field.as_deref(), Some(&b"hi"[..]), "{json}");
+            }
+        }
+
+        #[test]
+        fn test_as_opt_bytes_with_native_bytes() {
+            let deserialized = as_opt_bytes(BytesDeserializer::<Error>::new(b"hi")).unwrap();
+            assert_eq!(deserialized.as_deref(), Some(&b"hi"[..]));
+        }
+
+        #[test]
+        fn test_as_opt_bytes_with_null_and_empty_string() {
+            for json in [r#"{"field": null}"#, r#"{"field": ""}"#] {
+                let deserialized: TestOptBytesStruct = serde_json::from_str(json).unwrap();
+                assert_eq!(deserialized.field, None);
+            }
+        }
+
+        #[test]
+        fn test_as_opt_bytes_with_invalid_values() {
+            for json in [
+                r#"{"field": "not bytes!", "other": 1}"#,
+                r#"{"field": [1, 256, "x", [2], {"a": 3}], "other": 1}"#,
+                r#"{"field": true, "other": 1}"#,
+            ] {
+                let deserialized: TestOptBytesStruct = serde_json::from_str(json).unwrap();
+                assert_eq!(
+                    deserialized,
+                    TestOptBytesStruct {
+                        field: None,
+                        other: 1
+                    },
+                    "{json}"
+                );
+            }
+        }
+    }
+}
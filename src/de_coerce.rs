@@ -0,0 +1,841 @@
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::fmt;
+use core::marker::PhantomData;
+
+use crate::coerce::{self, CoerceError};
+use crate::de::{self, Deserialize, Deserializer, Visitor};
+use crate::de_impl::{as_bool, as_char, as_f64, as_i64, as_opt_bool, as_opt_char, as_opt_f64};
+use crate::de_impl::{as_opt_i64, as_opt_u64, as_u64};
+#[cfg(feature = "alloc")]
+use crate::de_impl::{as_opt_string, as_string};
+use crate::lenient::Lenient;
+use crate::typed::is_typed;
+use crate::visitor::{
+    DeserializeBoolWithVisitor, DeserializeCharWithVisitor, DeserializeF64WithVisitor,
+    DeserializeI64WithVisitor, DeserializeOptionalBoolWithVisitor,
+    DeserializeOptionalCharWithVisitor, DeserializeOptionalF64WithVisitor,
+    DeserializeOptionalI64WithVisitor, DeserializeOptionalU64WithVisitor,
+    DeserializeU64WithVisitor,
+};
+#[cfg(feature = "alloc")]
+use crate::visitor::{DeserializeOptionalStringWithVisitor, DeserializeStringWithVisitor};
+
+/// A type which can be *coerced* from multiple types, by way of the
+/// [`Visitor`] types associated with it.
+///
+/// This is implemented for each type supported by the helper functions, so
+/// that generic code can use [`as_coerce`] and [`as_opt_coerce`] in place of
+/// (say) [`as_u64`](crate::as_u64) and [`as_opt_u64`](crate::as_opt_u64):
+///
+/// ```rust
+/// use serde::Deserialize;
+/// use serde_this_or_that::{as_coerce, Coerce};
+///
+/// #[derive(Deserialize)]
+/// struct Metric<T: Coerce> {
+///     #[serde(deserialize_with = "as_coerce")]
+///     value: T,
+/// }
+///
+/// let m: Metric<u64> = serde_json::from_str(r#"{"value": "3"}"#).unwrap();
+/// assert_eq!(m.value, 3);
+///
+/// let m: Metric<f64> = serde_json::from_str(r#"{"value": "2.5"}"#).unwrap();
+/// assert_eq!(m.value, 2.5);
+/// ```
+///
+/// The integer types smaller than 64 bits, and `f32`, are coerced with the
+/// rules for `u64`, `i64`, or `f64`; an integer which does not fit in the
+/// smaller type, such as `"300"` for a `u8`, results in an error (or in
+/// [`None`], for [`as_opt_coerce`]).
+///
+/// It can also be implemented for other types, which then work with
+/// [`as_coerce`], [`as_opt_coerce`], [`Coerced`](crate::Coerced), and
+/// [`Lenient`](crate::Lenient). The
+/// [`DeserializeOptionalWithVisitor`](crate::visitor::DeserializeOptionalWithVisitor)
+/// adapter applies the crate's conventions for optional values to any
+/// visitor:
+///
+/// ```rust
+/// use std::fmt;
+///
+/// use serde::de::{self, Visitor};
+/// use serde_this_or_that::visitor::DeserializeOptionalWithVisitor;
+/// use serde_this_or_that::{Coerce, Lenient};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Level(u8);
+///
+/// #[derive(Default)]
+/// struct LevelVisitor;
+///
+/// impl Visitor<'_> for LevelVisitor {
+///     type Value = Level;
+///
+///     fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         formatter.write_str("a level from 1 to 5")
+///     }
+///
+///     fn visit_u64<E: de::Error>(self, v: u64) -> Result<Level, E> {
+///         match v {
+///             1..=5 => Ok(Level(v as u8)),
+///             _ => Err(E::invalid_value(de::Unexpected::Unsigned(v), &self)),
+///         }
+///     }
+///
+///     fn visit_str<E: de::Error>(self, v: &str) -> Result<Level, E> {
+///         match v.parse() {
+///             Ok(n) => self.visit_u64(n),
+///             Err(_) => Err(E::invalid_value(de::Unexpected::Str(v), &self)),
+///         }
+///     }
+/// }
+///
+/// impl Coerce for Level {
+///     type Visitor = LevelVisitor;
+///     type OptVisitor = DeserializeOptionalWithVisitor<LevelVisitor>;
+/// }
+///
+/// let levels: Vec<Lenient<Level>> = serde_json::from_str(r#"[1, "5"]"#).unwrap();
+/// assert_eq!(levels, [Lenient(Level(1)), Lenient(Level(5))]);
+/// ```
+pub trait Coerce: Sized {
+    /// The visitor used by [`as_coerce`].
+    type Visitor: for<'de> Visitor<'de, Value = Self> + Default;
+    /// The visitor used by [`as_opt_coerce`].
+    type OptVisitor: for<'de> Visitor<'de, Value = Option<Self>> + Default;
+
+    /// De-serialize a value with the [`Visitor`](Coerce::Visitor).
+    ///
+    /// By default, this calls `deserialize_any`; with the `typed` feature,
+    /// the implementations for the crate's own types instead request the
+    /// concrete type from formats which are not human-readable, such as
+    /// `bincode`, as described for [`define_coercion!`](crate::define_coercion).
+    fn coerce<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(Self::Visitor::default())
+    }
+
+    /// De-serialize an optional value with the
+    /// [`OptVisitor`](Coerce::OptVisitor).
+    ///
+    /// By default, this calls `deserialize_any`, like [`Coerce::coerce`].
+    fn coerce_opt<'de, D>(deserializer: D) -> Result<Option<Self>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(Self::OptVisitor::default())
+    }
+
+    /// De-serialize a map key for [`as_map_keys`](crate::as_map_keys).
+    ///
+    /// By default, a string key is passed on to the
+    /// [`Visitor`](Coerce::Visitor) without its surrounding whitespace, so
+    /// that `" 7"` is read as `7`. The implementation for `String` keeps the
+    /// key as-is, and the one for `bool` rejects a string which is neither
+    /// "truthy" nor "falsy", rather than reading it as `false`.
+    fn coerce_key<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if is_typed(&deserializer) {
+            Self::coerce(deserializer)
+        } else {
+            deserializer.deserialize_any(TrimmedKeyVisitor(Self::Visitor::default()))
+        }
+    }
+}
+
+macro_rules! impl_coerce {
+    ($(
+        $(#[$attr:meta])*
+        $ty:ty => $visitor:ident, $opt_visitor:ident, $as_fn:ident, $as_opt_fn:ident
+            $(, key = $key_fn:ident)?;
+    )*) => {
+        $(
+            $(#[$attr])*
+            impl Coerce for $ty {
+                type Visitor = $visitor;
+                type OptVisitor = $opt_visitor;
+
+                #[inline]
+                fn coerce<'de, D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    $as_fn(deserializer)
+                }
+
+                #[inline]
+                fn coerce_opt<'de, D>(deserializer: D) -> Result<Option<Self>, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    $as_opt_fn(deserializer)
+                }
+
+                $(
+                    #[inline]
+                    fn coerce_key<'de, D>(deserializer: D) -> Result<Self, D::Error>
+                    where
+                        D: Deserializer<'de>,
+                    {
+                        $key_fn(deserializer)
+                    }
+                )?
+            }
+        )*
+    };
+}
+
+impl_coerce! {
+    u64 => DeserializeU64WithVisitor, DeserializeOptionalU64WithVisitor, as_u64, as_opt_u64;
+    i64 => DeserializeI64WithVisitor, DeserializeOptionalI64WithVisitor, as_i64, as_opt_i64;
+    f64 => DeserializeF64WithVisitor, DeserializeOptionalF64WithVisitor, as_f64, as_opt_f64;
+    bool => DeserializeBoolWithVisitor, DeserializeOptionalBoolWithVisitor, as_bool, as_opt_bool,
+        key = as_bool_key;
+    char => DeserializeCharWithVisitor, DeserializeOptionalCharWithVisitor, as_char, as_opt_char;
+    #[cfg(feature = "alloc")]
+    String => DeserializeStringWithVisitor, DeserializeOptionalStringWithVisitor, as_string,
+        as_opt_string, key = as_string;
+}
+
+/// De-serialize a map key as a *boolean*, as [`as_bool`] does, but with an
+/// error for a string which is neither "truthy" nor "falsy".
+fn as_bool_key<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if is_typed(&deserializer) {
+        as_bool(deserializer)
+    } else {
+        deserializer.deserialize_any(TrimmedKeyVisitor(BoolKeyVisitor))
+    }
+}
+
+/// A visitor which passes a key on to the inner visitor, without the
+/// surrounding whitespace of a string key.
+struct TrimmedKeyVisitor<V>(V);
+
+impl<'de, V> Visitor<'de> for TrimmedKeyVisitor<V>
+where
+    V: Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.expecting(formatter)
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_bool(v)
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_i64(v)
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_u64(v)
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_f64(v)
+    }
+
+    fn visit_char<E>(self, v: char) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_char(v)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_str(v.trim())
+    }
+
+    #[cfg(feature = "alloc")]
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if v.trim() == v {
+            self.0.visit_string(v)
+        } else {
+            self.0.visit_str(v.trim())
+        }
+    }
+}
+
+/// A visitor for a *boolean* map key, which rejects a string that is
+/// neither "truthy" nor "falsy".
+struct BoolKeyVisitor;
+
+impl<'de> Visitor<'de> for BoolKeyVisitor {
+    type Value = bool;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        DeserializeBoolWithVisitor.expecting(formatter)
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        DeserializeBoolWithVisitor.visit_bool(v)
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        DeserializeBoolWithVisitor.visit_i64(v)
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        DeserializeBoolWithVisitor.visit_u64(v)
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        DeserializeBoolWithVisitor.visit_f64(v)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        match coerce::str_to_bool(v) {
+            Err(CoerceError::Invalid) => Err(E::invalid_value(de::Unexpected::Str(v), &self)),
+            _ => DeserializeBoolWithVisitor.visit_str(v),
+        }
+    }
+}
+
+/// Implements [`Coerce`] for a type which is narrower than that of one of
+/// the crate's visitors, by way of a [`DeserializeNarrowWithVisitor`]. The
+/// `$hint` is used for formats which are not human-readable, with the
+/// `typed` feature.
+macro_rules! impl_coerce_narrow {
+    ($($ty:ty => $visitor:ident, $opt_visitor:ident, $hint:ident;)*) => {
+        $(
+            impl Coerce for $ty {
+                type Visitor = DeserializeNarrowWithVisitor<$visitor, $ty>;
+                type OptVisitor = DeserializeNarrowWithVisitor<$opt_visitor, Option<$ty>>;
+
+                fn coerce<'de, D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    if is_typed(&deserializer) {
+                        deserializer.$hint(Self::Visitor::default())
+                    } else {
+                        deserializer.deserialize_any(Self::Visitor::default())
+                    }
+                }
+
+                fn coerce_opt<'de, D>(deserializer: D) -> Result<Option<Self>, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    if is_typed(&deserializer) {
+                        Option::<Lenient<$ty>>::deserialize(deserializer)
+                            .map(|opt| opt.map(Lenient::into_inner))
+                    } else {
+                        deserializer.deserialize_any(Self::OptVisitor::default())
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_coerce_narrow! {
+    u8 => DeserializeU64WithVisitor, DeserializeOptionalU64WithVisitor, deserialize_u8;
+    u16 => DeserializeU64WithVisitor, DeserializeOptionalU64WithVisitor, deserialize_u16;
+    u32 => DeserializeU64WithVisitor, DeserializeOptionalU64WithVisitor, deserialize_u32;
+    usize => DeserializeU64WithVisitor, DeserializeOptionalU64WithVisitor, deserialize_u64;
+    i8 => DeserializeI64WithVisitor, DeserializeOptionalI64WithVisitor, deserialize_i8;
+    i16 => DeserializeI64WithVisitor, DeserializeOptionalI64WithVisitor, deserialize_i16;
+    i32 => DeserializeI64WithVisitor, DeserializeOptionalI64WithVisitor, deserialize_i32;
+    isize => DeserializeI64WithVisitor, DeserializeOptionalI64WithVisitor, deserialize_i64;
+    f32 => DeserializeF64WithVisitor, DeserializeOptionalF64WithVisitor, deserialize_f32;
+}
+
+/// De-serialize a value of any type `T` which implements [`Coerce`], using
+/// its [`Visitor`](Coerce::Visitor) (by way of [`Coerce::coerce`]).
+///
+/// # Errors
+/// Returns an error if the visitor does.
+///
+/// # Returns
+/// The `T` value, such as the unsigned (`u64`) value of a string or number
+/// when `T` is `u64`.
+///
+pub fn as_coerce<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Coerce,
+{
+    T::coerce(deserializer)
+}
+
+/// De-serialize a value of any type `T` which implements [`Coerce`] as an
+/// *optional* value, using its [`OptVisitor`](Coerce::OptVisitor) (by way of
+/// [`Coerce::coerce_opt`]).
+///
+/// # Returns
+/// A [`Some`] with the `T` value, or [`None`] for a `null` value, or one
+/// of an *invalid* data type.
+///
+pub fn as_opt_coerce<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Coerce,
+{
+    T::coerce_opt(deserializer)
+}
+
+/// A [`Visitor`] which returns the value of the inner visitor `V` wrapped
+/// in [`Some`], with the same conventions as the crate's `as_opt` helpers:
+/// a `null` value or an empty string results in [`None`], and so does an
+/// error from the inner visitor.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DeserializeOptionalWithVisitor<V>(pub V);
+
+impl<'de, V> Visitor<'de> for DeserializeOptionalWithVisitor<V>
+where
+    V: Visitor<'de>,
+{
+    type Value = Option<V::Value>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.expecting(formatter)
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(self.0.visit_bool::<E>(v).ok())
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(self.0.visit_i64::<E>(v).ok())
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(self.0.visit_u64::<E>(v).ok())
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(self.0.visit_f64::<E>(v).ok())
+    }
+
+    fn visit_char<E>(self, v: char) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(self.0.visit_char::<E>(v).ok())
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if v.is_empty() {
+            Ok(None)
+        } else {
+            Ok(self.0.visit_str::<E>(v).ok())
+        }
+    }
+
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if v.is_empty() {
+            Ok(None)
+        } else {
+            Ok(self.0.visit_borrowed_str::<E>(v).ok())
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if v.is_empty() {
+            Ok(None)
+        } else {
+            Ok(self.0.visit_string::<E>(v).ok())
+        }
+    }
+
+    /// We encounter a `null` value; this default implementation returns an
+    /// `Option::None` value.
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(None)
+    }
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(None)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        crate::de_wrapped::visit_wrapped_map(map, self)
+    }
+}
+
+/// Converts the value of one of the crate's visitors to a (possibly
+/// smaller) type, handing the value back if it is out of range.
+///
+/// This is only implemented by the crate, for the types which are used with
+/// a [`DeserializeNarrowWithVisitor`].
+pub trait Narrow<W>: Sized {
+    /// Converts the `value`, or hands it back if it is out of range.
+    fn narrow(value: W) -> Result<Self, W>;
+}
+
+impl Narrow<f64> for f32 {
+    #[inline]
+    fn narrow(value: f64) -> Result<Self, f64> {
+        Ok(value as f32)
+    }
+}
+
+macro_rules! impl_narrow {
+    ($wide:ty => $($ty:ty),*) => {
+        $(
+            impl Narrow<$wide> for $ty {
+                #[inline]
+                fn narrow(value: $wide) -> Result<Self, $wide> {
+                    <$ty>::try_from(value).map_err(|_| value)
+                }
+            }
+        )*
+    };
+}
+
+impl_narrow!(u64 => u8, u16, u32, usize);
+impl_narrow!(i64 => i8, i16, i32, isize);
+
+/// An optional value which is out of range results in [`None`], as with the
+/// crate's `as_opt` helpers.
+impl<W, T> Narrow<Option<W>> for Option<T>
+where
+    T: Narrow<W>,
+{
+    #[inline]
+    fn narrow(value: Option<W>) -> Result<Self, Option<W>> {
+        Ok(value.and_then(|v| T::narrow(v).ok()))
+    }
+}
+
+/// A [`Visitor`] which converts the value of the inner visitor `V` to a
+/// smaller type `T`, such as a `u8` from the `u64` of a
+/// [`DeserializeU64WithVisitor`]. A value which is out of range results in
+/// an error.
+///
+/// This is the visitor of the [`Coerce`] implementations for the integer
+/// types smaller than 64 bits, and `f32`.
+pub struct DeserializeNarrowWithVisitor<V, T>(V, PhantomData<T>);
+
+impl<V: Default, T> Default for DeserializeNarrowWithVisitor<V, T> {
+    fn default() -> Self {
+        Self(V::default(), PhantomData)
+    }
+}
+
+impl<V: Clone, T> Clone for DeserializeNarrowWithVisitor<V, T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone(), PhantomData)
+    }
+}
+
+impl<V: Copy, T> Copy for DeserializeNarrowWithVisitor<V, T> {}
+
+impl<V: fmt::Debug, T> fmt::Debug for DeserializeNarrowWithVisitor<V, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("DeserializeNarrowWithVisitor")
+            .field(&self.0)
+            .finish()
+    }
+}
+
+impl<'de, V, T> DeserializeNarrowWithVisitor<V, T>
+where
+    V: Visitor<'de>,
+    V::Value: fmt::Debug,
+    T: Narrow<V::Value>,
+{
+    #[inline]
+    fn narrow<E>(value: V::Value) -> Result<T, E>
+    where
+        E: de::Error,
+    {
+        T::narrow(value).map_err(|v| {
+            de::Error::custom(format_args!(
+                "overflow: Unable to convert value `{v:?}` to {}",
+                core::any::type_name::<T>(),
+            ))
+        })
+    }
+}
+
+/// Defines `visit_*` methods which pass the value on to the inner visitor,
+/// and convert its result.
+macro_rules! visit_narrow {
+    ($($method:ident($ty:ty);)*) => {
+        $(
+            fn $method<E>(self, v: $ty) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                self.0.$method(v).and_then(Self::narrow)
+            }
+        )*
+    };
+}
+
+impl<'de, V, T> Visitor<'de> for DeserializeNarrowWithVisitor<V, T>
+where
+    V: Visitor<'de>,
+    V::Value: fmt::Debug,
+    T: Narrow<V::Value>,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.expecting(formatter)
+    }
+
+    visit_narrow! {
+        visit_bool(bool);
+        visit_i64(i64);
+        visit_u64(u64);
+        visit_f64(f64);
+        visit_char(char);
+        visit_str(&str);
+        visit_borrowed_str(&'de str);
+    }
+
+    #[cfg(feature = "alloc")]
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_string(v).and_then(Self::narrow)
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_unit().and_then(Self::narrow)
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_none().and_then(Self::narrow)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.0.visit_some(deserializer).and_then(Self::narrow)
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.0
+            .visit_newtype_struct(deserializer)
+            .and_then(Self::narrow)
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        self.0.visit_map(map).and_then(Self::narrow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct TestCoerceStruct<T: Coerce> {
+        #[serde(deserialize_with = "as_coerce")]
+        value: T,
+        #[serde(default, deserialize_with = "as_opt_coerce")]
+        limit: Option<T>,
+    }
+
+    #[test]
+    fn test_as_coerce_dispatches_on_type() {
+        let json = r#"{"value": "3", "limit": "x"}"#;
+
+        let deserialized: TestCoerceStruct<u64> = serde_json::from_str(json).unwrap();
+        assert_eq!(deserialized.value, 3);
+        assert_eq!(deserialized.limit, None);
+
+        let deserialized: TestCoerceStruct<String> = serde_json::from_str(json).unwrap();
+        assert_eq!(deserialized.value, "3");
+        assert_eq!(deserialized.limit.as_deref(), Some("x"));
+
+        let json = r#"{"value": "yes", "limit": 0}"#;
+        let deserialized: TestCoerceStruct<bool> = serde_json::from_str(json).unwrap();
+        assert!(deserialized.value);
+        assert_eq!(deserialized.limit, Some(false));
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Metric<T: Coerce> {
+        #[serde(deserialize_with = "as_coerce")]
+        value: T,
+        #[serde(default, deserialize_with = "as_opt_coerce")]
+        limit: Option<T>,
+        coerced: crate::Coerced<T>,
+        patch: crate::Patch<T>,
+        #[serde(deserialize_with = "crate::as_map_of")]
+        by_name: std::collections::BTreeMap<String, T>,
+    }
+
+    #[test]
+    fn test_as_coerce_with_narrow_types() {
+        let json = r#"{
+            "value": "3", "limit": "300", "coerced": "7", "patch": 4.0,
+            "by_name": {"a": "1", "b": 2}
+        }"#;
+
+        let deserialized: Metric<u8> = serde_json::from_str(json).unwrap();
+        assert_eq!(deserialized.value, 3);
+        assert_eq!(deserialized.limit, None);
+        assert_eq!(*deserialized.coerced, 7);
+        assert_eq!(deserialized.patch, crate::Patch::Value(4));
+        assert_eq!(deserialized.by_name["b"], 2);
+
+        let deserialized: Metric<u32> = serde_json::from_str(json).unwrap();
+        assert_eq!(deserialized.limit, Some(300));
+
+        let deserialized: Metric<u16> = serde_json::from_str(json).unwrap();
+        assert_eq!(deserialized.by_name["a"], 1);
+
+        let deserialized: Metric<i8> = serde_json::from_str(json).unwrap();
+        assert_eq!(deserialized.value, 3);
+        assert_eq!(deserialized.limit, None);
+
+        let deserialized: Metric<f32> = serde_json::from_str(json).unwrap();
+        assert_eq!(deserialized.limit, Some(300.0));
+
+        // A value which does not fit in the type is an error.
+        let json = r#"{"value": "-129", "coerced": 1, "patch": null, "by_name": {}}"#;
+        let deserialized = serde_json::from_str::<Metric<i8>>(json);
+        assert!(deserialized
+            .unwrap_err()
+            .to_string()
+            .contains("Unable to convert value `-129` to i8"));
+
+        let deserialized: Metric<i16> = serde_json::from_str(json).unwrap();
+        assert_eq!(deserialized.value, -129);
+        assert_eq!(deserialized.patch, crate::Patch::Null);
+    }
+
+    #[test]
+    fn test_as_coerce_with_invalid_value() {
+        let json = r#"{"value": "x"}"#;
+        let deserialized = serde_json::from_str::<TestCoerceStruct<f64>>(json);
+        assert!(deserialized.is_err());
+    }
+
+    #[test]
+    fn test_optional_with_visitor() {
+        fn as_opt_char<'de, D>(deserializer: D) -> Result<Option<char>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_any(DeserializeOptionalWithVisitor(DeserializeCharWithVisitor))
+        }
+
+        #[derive(Deserialize)]
+        struct TestOptCharStruct {
+            #[serde(deserialize_with = "as_opt_char")]
+            field: Option<char>,
+        }
+
+        for (json, expected) in [
+            (r#"{"field": "Y"}"#, Some('Y')),
+            (r#"{"field": 89}"#, Some('Y')),
+            (r#"{"field": ""}"#, None),
+            (r#"{"field": null}"#, None),
+            (r#"{"field": "YES"}"#, None),
+            (r#"{"field": true}"#, None),
+        ] {
+            let deserialized: TestOptCharStruct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized.field, expected, "{json}");
+        }
+    }
+}
@@ -1,423 +1,1418 @@
-use std::{f64, fmt};
-
+#[cfg(feature = "alloc")]
+use alloc::{
+    borrow::{Cow, ToOwned},
+    string::{String, ToString},
+};
+use core::fmt;
+
+use crate::coerce::{self, CoerceError};
 use crate::de::{self, Deserializer, Unexpected};
+use crate::typed::is_typed;
+
+crate::define_coercion! {
+    /// De-serialize either a `null`, `str`, `i64`, `f64`, or `u64`
+    /// as a *signed* value.
+    ///
+    /// # Errors
+    /// Returns an error if a string is non-empty and not a valid numeric
+    /// value, or if the unsigned value `u64` *overflows* when converted
+    /// to `i64`.
+    ///
+    /// # Returns
+    /// The signed (`i64`) value of a string or number.
+    ///
+    pub fn as_i64 -> i64, DeserializeI64WithVisitor;
+    /// De-serialize either a `str`, `i64`, `f64`, or `u64`
+    /// as a *signed* value wrapped in [`Some`],
+    /// and a `bool` or `null` value as [`None`].
+    ///
+    /// # Returns
+    /// A [`Some`] with the signed (`i64`) value of a string
+    /// or number.
+    ///
+    /// A [`None`] in the case of:
+    ///   * a `bool` value.
+    ///   * a `null` value.
+    ///   * any *de-serialization* errors.
+    ///     * ex. a string is non-empty and not a valid numeric value.
+    ///     * ex. the unsigned value `u64` *overflows* when converted to `i64`.
+    ///
+    pub fn as_opt_i64, DeserializeOptionalI64WithVisitor;
+
+    expecting = "a signed integer or a string";
+    hint = deserialize_i64;
+
+    value {
+        i64(v) => Ok(observed!("as_i64", I64, Lossless, v)),
+        u64(v) => match coerce::u64_to_i64(v) {
+            Ok(n) => Ok(observed!("as_i64", U64, Lossless, n)),
+            Err(_) => Err(de::Error::custom(format_args!(
+                "overflow: Unable to convert unsigned value `{v:?}` to i64"
+            ))),
+        },
+        f64(v) => {
+            let n = coerce::f64_to_i64(v);
+            Ok(observed!(
+                "as_i64",
+                F64,
+                if n as f64 == v { Lossless } else { Lossy },
+                n
+            ))
+        },
+        str(v) => match coerce::str_to_i64(v) {
+            Ok(n) => Ok(observed!(
+                "as_i64",
+                Str,
+                if coerce::is_exact(v, n as f64) { Lossless } else { Lossy },
+                n
+            )),
+            Err(CoerceError::Empty) => Ok(observed!("as_i64", Str, Defaulted, 0)),
+            Err(_) => Err(de::Error::invalid_value(Unexpected::Str(v), &DeserializeI64WithVisitor)),
+        },
+        // We encounter a `null` value; this default implementation returns a
+        // "zero" value.
+        unit => Ok(observed!("as_i64", Null, Defaulted, 0)),
+    }
+
+    option {
+        bool(_) => observed!("as_opt_i64", Bool, Dropped, None),
+        i64(v) => observed!("as_opt_i64", I64, Lossless, Some(v)),
+        u64(v) => observed!(
+            "as_opt_i64",
+            U64,
+            o = coerce::u64_to_i64(v).ok(),
+            if o.is_some() { Lossless } else { Dropped }
+        ),
+        f64(v) => {
+            let n = coerce::f64_to_i64(v);
+            observed!(
+                "as_opt_i64",
+                F64,
+                if n as f64 == v { Lossless } else { Lossy },
+                Some(n)
+            )
+        },
+        str(v) => match coerce::str_to_i64(v) {
+            Ok(n) => observed!(
+                "as_opt_i64",
+                Str,
+                if coerce::is_exact(v, n as f64) { Lossless } else { Lossy },
+                Some(n)
+            ),
+            Err(CoerceError::Empty) => observed!("as_opt_i64", Str, Defaulted, None),
+            Err(_) => observed!("as_opt_i64", Str, Dropped, None),
+        },
+        // We encounter a `null` value; this default implementation returns an
+        // `Option::None` value.
+        unit => observed!("as_opt_i64", Null, Defaulted, None),
+    }
+}
+
+crate::define_coercion! {
+    /// De-serialize either a `null`, `str`, `u64`, `f64`, or `i64`
+    /// as an *unsigned* value.
+    ///
+    /// # Errors
+    /// Returns an error if a string is non-empty and not a valid numeric
+    /// value, or if the signed value `i64` represents a *negative* number.
+    ///
+    /// # Returns
+    /// The unsigned (`u64`) value of a string or number.
+    ///
+    pub fn as_u64 -> u64, DeserializeU64WithVisitor;
+    /// De-serialize either a `str`, `u64`, `f64`, or `i64`
+    /// as an *unsigned* value wrapped in [`Some`],
+    /// and a `bool` or `null` value as [`None`].
+    ///
+    /// # Returns
+    /// A [`Some`] with the unsigned (`u64`) value of a string
+    /// or number.
+    ///
+    /// A [`None`] in the case of:
+    ///   * a `bool` value.
+    ///   * a `null` value.
+    ///   * any *de-serialization* errors.
+    ///     * ex. a string is non-empty and not a valid numeric value.
+    ///     * ex. the signed value `i64` represents a *negative* number.
+    ///     * ex. float `f64` represents a *negative* number `< -0.5`, or `NaN`.
+    ///
+    pub fn as_opt_u64, DeserializeOptionalU64WithVisitor;
+
+    expecting = "an unsigned integer or a string";
+    hint = deserialize_u64;
+
+    value {
+        i64(v) => match coerce::i64_to_u64(v) {
+            Ok(n) => Ok(observed!("as_u64", I64, Lossless, n)),
+            Err(_) => Err(de::Error::custom(format_args!(
+                "overflow: Unable to convert signed value `{v:?}` to u64"
+            ))),
+        },
+        u64(v) => Ok(observed!("as_u64", U64, Lossless, v)),
+        f64(v) => {
+            let n = coerce::f64_to_u64(v);
+            Ok(observed!(
+                "as_u64",
+                F64,
+                if n as f64 == v { Lossless } else { Lossy },
+                n
+            ))
+        },
+        str(v) => match coerce::str_to_u64(v) {
+            Ok(n) => Ok(observed!(
+                "as_u64",
+                Str,
+                if coerce::is_exact(v, n as f64) { Lossless } else { Lossy },
+                n
+            )),
+            Err(CoerceError::Empty) => Ok(observed!("as_u64", Str, Defaulted, 0)),
+            Err(_) => Err(de::Error::invalid_value(Unexpected::Str(v), &DeserializeU64WithVisitor)),
+        },
+        // We encounter a `null` value; this default implementation returns a
+        // "zero" value.
+        unit => Ok(observed!("as_u64", Null, Defaulted, 0)),
+    }
+
+    option {
+        bool(_) => observed!("as_opt_u64", Bool, Dropped, None),
+        i64(v) => observed!(
+            "as_opt_u64",
+            I64,
+            o = coerce::i64_to_u64(v).ok(),
+            if o.is_some() { Lossless } else { Dropped }
+        ),
+        u64(v) => observed!("as_opt_u64", U64, Lossless, Some(v)),
+        f64(v) => {
+            let n = coerce::f64_to_u64(v);
+            observed!(
+                "as_opt_u64",
+                F64,
+                if n as f64 == v { Lossless } else { Lossy },
+                Some(n)
+            )
+        },
+        str(v) => match coerce::str_to_u64(v) {
+            Ok(n) => observed!(
+                "as_opt_u64",
+                Str,
+                if coerce::is_exact(v, n as f64) { Lossless } else { Lossy },
+                Some(n)
+            ),
+            Err(CoerceError::Empty) => observed!("as_opt_u64", Str, Defaulted, None),
+            Err(_) => observed!("as_opt_u64", Str, Dropped, None),
+        },
+        // We encounter a `null` value; this default implementation returns an
+        // `Option::None` value.
+        unit => observed!("as_opt_u64", Null, Defaulted, None),
+    }
+}
+
+crate::define_coercion! {
+    /// De-serialize either a `null`, `str`, `f64`, `u64`, or `i64`
+    /// as a *float* value.
+    ///
+    /// # Errors
+    /// Returns an error if a string is non-empty and not a valid numeric value.
+    ///
+    /// # Returns
+    /// The floating point (`f64`) value of a string or number.
+    ///
+    pub fn as_f64 -> f64, DeserializeF64WithVisitor;
+    /// De-serialize either a `str`, `f64`, `u64`, or `i64`
+    /// as a *float* value wrapped in [`Some`],
+    /// and a `bool` or `null` value as [`None`].
+    ///
+    /// # Returns
+    /// A [`Some`] with the floating point (`f64`) value of a string
+    /// or number.
+    ///
+    /// A [`None`] in the case of:
+    ///   * a `bool` value.
+    ///   * a `null` value.
+    ///   * any *de-serialization* errors.
+    ///     * ex. a string is non-empty and not a valid numeric value.
+    ///
+    pub fn as_opt_f64, DeserializeOptionalF64WithVisitor;
+
+    expecting = "a float or a string";
+    hint = deserialize_f64;
+
+    value {
+        i64(v) => Ok(observed!(
+            "as_f64",
+            I64,
+            f = v as f64,
+            if f as i128 == v as i128 { Lossless } else { Lossy }
+        )),
+        u64(v) => Ok(observed!(
+            "as_f64",
+            U64,
+            f = v as f64,
+            if f as i128 == v as i128 { Lossless } else { Lossy }
+        )),
+        f64(v) => Ok(observed!("as_f64", F64, Lossless, v)),
+        str(v) => match coerce::str_to_f64(v) {
+            Ok(f) => Ok(observed!("as_f64", Str, Lossless, f)),
+            Err(CoerceError::Empty) => Ok(observed!("as_f64", Str, Defaulted, 0.0)),
+            Err(_) => Err(de::Error::invalid_value(Unexpected::Str(v), &DeserializeF64WithVisitor)),
+        },
+        // We encounter a `null` value; this default implementation returns a
+        // "zero" value.
+        unit => Ok(observed!("as_f64", Null, Defaulted, 0.0)),
+    }
+
+    option {
+        bool(_) => observed!("as_opt_f64", Bool, Dropped, None),
+        i64(v) => observed!(
+            "as_opt_f64",
+            I64,
+            o = Some(v as f64),
+            if o.map(|f| f as i128) == Some(v as i128) { Lossless } else { Lossy }
+        ),
+        u64(v) => observed!(
+            "as_opt_f64",
+            U64,
+            o = Some(v as f64),
+            if o.map(|f| f as i128) == Some(v as i128) { Lossless } else { Lossy }
+        ),
+        f64(v) => observed!("as_opt_f64", F64, Lossless, Some(v)),
+        str(v) => match coerce::str_to_f64(v) {
+            Ok(f) => observed!("as_opt_f64", Str, Lossless, Some(f)),
+            Err(CoerceError::Empty) => observed!("as_opt_f64", Str, Defaulted, None),
+            Err(_) => observed!("as_opt_f64", Str, Dropped, None),
+        },
+        // We encounter a `null` value; this default implementation returns an
+        // `Option::None` value.
+        unit => observed!("as_opt_f64", Null, Defaulted, None),
+    }
+}
+
+crate::define_coercion! {
+    /// De-serialize either a `null`, `bool`, `str`, `u64`, or `f64`
+    /// as a *boolean* value.
+    ///
+    /// # Truthy String Values
+    /// > Note: the pattern matching is *case insensitive*, so `YES` or `yes`
+    /// > works just the same.
+    ///
+    /// These are the following "truthy" string values that result in a
+    /// boolean value of `true`:
+    ///
+    ///   - `1`
+    ///   - `OK`
+    ///   - `ON`
+    ///   - `T`
+    ///   - `TRUE`
+    ///   - `Y`
+    ///   - `YES`
+    ///
+    /// # Errors
+    /// Returns an error if an unsigned `u64` or a float `f64` value is not
+    /// a *zero* or a *one*.
+    ///
+    /// # Returns
+    /// The boolean (`bool`) value of a string, boolean, or number.
+    ///
+    pub fn as_bool -> bool, DeserializeBoolWithVisitor;
+    /// De-serialize either a `bool`, `str`, `u64`, or `f64`
+    /// as a *boolean* value wrapped in [`Some`],
+    /// and an `i64` or `null` value as [`None`].
+    ///
+    /// # Truthy String Values
+    /// > Note: the pattern matching is *case insensitive*, so `YES` or `yes`
+    /// > works just the same.
+    ///
+    /// These are the following "truthy" string values that result in a
+    /// boolean value of `true`:
+    ///
+    ///   - `1`
+    ///   - `OK`
+    ///   - `ON`
+    ///   - `T`
+    ///   - `TRUE`
+    ///   - `Y`
+    ///   - `YES`
+    ///
+    /// # Falsy String Values
+    /// > Note: the pattern matching is *case insensitive*, so `NO` or `no`
+    /// > works just the same.
+    ///
+    /// These are the following "falsy" string values that result in a
+    /// boolean value of `false`:
+    ///
+    ///   - `0`
+    ///   - `NG` ([antonym for `OK`](https://english.stackexchange.com/a/586568/461000))
+    ///   - `OFF`
+    ///   - `F`
+    ///   - `FALSE`
+    ///   - `N`
+    ///   - `NO`
+    ///
+    /// # Returns
+    /// A [`Some`] with the boolean (`bool`) value of a string,
+    /// boolean, or number.
+    ///
+    /// A [`None`] in the case of:
+    ///   * a `str` value which does not match any of the ["truthy"](#truthy-string-values)
+    ///     or ["falsy"](#falsy-string-values) values as defined above.
+    ///   * an `i64` value.
+    ///   * a `null` value.
+    ///   * any *de-serialization* errors.
+    ///     * ex. an unsigned `u64` or a float `f64` value is not a *zero* or a *one*.
+    ///
+    pub fn as_opt_bool, DeserializeOptionalBoolWithVisitor;
+
+    expecting = "an integer (0 or 1) or a string";
+    hint = deserialize_bool;
+
+    value {
+        bool(v) => Ok(observed!("as_bool", Bool, Lossless, v)),
+        i64(v) => Err(de::Error::invalid_value(
+            Unexpected::Signed(v),
+            &"zero or one",
+        )),
+        u64(v) => match coerce::u64_to_bool(v) {
+            Ok(b) => Ok(observed!("as_bool", U64, Lossless, b)),
+            Err(_) => Err(de::Error::invalid_value(
+                Unexpected::Unsigned(v),
+                &"zero or one",
+            )),
+        },
+        f64(v) => match coerce::f64_to_bool(v) {
+            Ok(b) => Ok(observed!(
+                "as_bool",
+                F64,
+                if v == f64::from(u8::from(b)) { Lossless } else { Lossy },
+                b
+            )),
+            Err(_) => Err(de::Error::invalid_value(
+                Unexpected::Float(v),
+                &"zero or one",
+            )),
+        },
+        str(v) => match coerce::str_to_bool(v) {
+            Ok(b) => Ok(observed!("as_bool", Str, Lossless, b)),
+            // Any other string, including an empty one, is "false".
+            Err(_) => Ok(observed!("as_bool", Str, Defaulted, false)),
+        },
+        // We encounter a `null` value; this default implementation returns a
+        // "false" value.
+        unit => Ok(observed!("as_bool", Null, Defaulted, false)),
+    }
+
+    option {
+        bool(v) => observed!("as_opt_bool", Bool, Lossless, Some(v)),
+        // needs a zero or one, just return `None` here
+        i64(_) => observed!("as_opt_bool", I64, Dropped, None),
+        // needs a zero or one, otherwise just return `None` here
+        u64(v) => observed!(
+            "as_opt_bool",
+            U64,
+            o = coerce::u64_to_bool(v).ok(),
+            if o.is_some() { Lossless } else { Dropped }
+        ),
+        f64(v) => observed!(
+            "as_opt_bool",
+            F64,
+            o = coerce::f64_to_bool(v).ok(),
+            match o {
+                Some(b) if v == f64::from(u8::from(b)) => Lossless,
+                Some(_) => Lossy,
+                None => Dropped,
+            }
+        ),
+        str(v) => match coerce::str_to_bool(v) {
+            Ok(b) => observed!("as_opt_bool", Str, Lossless, Some(b)),
+            Err(CoerceError::Empty) => observed!("as_opt_bool", Str, Defaulted, None),
+            Err(_) => observed!("as_opt_bool", Str, Dropped, None),
+        },
+        // We encounter a `null` value; this default implementation returns an
+        // `Option::None` value.
+        unit => observed!("as_opt_bool", Null, Defaulted, None),
+    }
+}
+
+#[cfg(feature = "alloc")]
+crate::define_coercion! {
+    /// De-serialize either a `null`, `str`, `bool`, `i64`, `f64`, or `u64`
+    /// as an (owned) *string* value.
+    ///
+    /// # Returns
+    /// The owned `String` value of a string, boolean, or number.
+    ///
+    pub fn as_string -> String, DeserializeStringWithVisitor;
+    /// De-serialize either a `str`, `bool`, `i64`, `f64`, or `u64`
+    /// as an (owned) *string* value wrapped in [`Some`],
+    /// and a `null` value as [`None`].
+    ///
+    /// # Returns
+    /// A [`Some`] with the owned `String` value of a string,
+    /// boolean, or number.
+    ///
+    /// A [`None`] in the case of:
+    ///   * a `null` value.
+    ///   * any *de-serialization* errors.
+    ///
+    pub fn as_opt_string, DeserializeOptionalStringWithVisitor;
+
+    expecting = "a string, bool, or a number";
+    hint = deserialize_string;
+
+    value {
+        bool(v) => Ok(observed!("as_string", Bool, Lossless, v.to_string())),
+        i64(v) => Ok(observed!("as_string", I64, Lossless, v.to_string())),
+        u64(v) => Ok(observed!("as_string", U64, Lossless, v.to_string())),
+        f64(v) => Ok(observed!("as_string", F64, Lossless, v.to_string())),
+        str(v) => Ok(observed!("as_string", Str, Lossless, v.to_owned())),
+        // The de-serializer hands over an owned `String`; take ownership of it
+        // rather than copying it.
+        string(v) => Ok(observed!("as_string", Str, Lossless, v)),
+        // We encounter a `null` value; this default implementation returns an
+        // "empty" string.
+        unit => Ok(observed!("as_string", Null, Defaulted, String::new())),
+    }
 
-/// De-serialize either a `null`, `str`, `i64`, `f64`, or `u64`
-/// as a *signed* value.
+    option {
+        bool(v) => observed!("as_opt_string", Bool, Lossless, Some(v.to_string())),
+        i64(v) => observed!("as_opt_string", I64, Lossless, Some(v.to_string())),
+        u64(v) => observed!("as_opt_string", U64, Lossless, Some(v.to_string())),
+        f64(v) => observed!("as_opt_string", F64, Lossless, Some(v.to_string())),
+        str(v) => observed!("as_opt_string", Str, Lossless, Some(v.to_owned())),
+        string(v) => observed!("as_opt_string", Str, Lossless, Some(v)),
+        // We encounter a `null` value; this default implementation returns an
+        // `Option::None` value.
+        unit => observed!("as_opt_string", Null, Defaulted, None),
+    }
+}
+
+crate::define_coercion! {
+    /// De-serialize either a `null`, `str`, `u64`, or `i64`
+    /// as a *character* value.
+    ///
+    /// A string is accepted if it contains exactly one character, either
+    /// as-is or after trimming any leading and trailing whitespace, so
+    /// `"Y"` and `" Y "` both result in `'Y'`. See [`as_char_with`] to not
+    /// trim the string.
+    ///
+    /// An integer is treated as a Unicode *code point*, so `89` also results
+    /// in `'Y'`.
+    ///
+    /// # Errors
+    /// Returns an error if a string is non-empty and not a single character,
+    /// or if an integer is not a valid Unicode scalar value.
+    ///
+    /// # Returns
+    /// The character (`char`) value of a string or code point. A `null`
+    /// value or an empty string results in the *null* character `'\0'`.
+    ///
+    pub fn as_char -> char, DeserializeCharWithVisitor;
+    /// De-serialize either a `str`, `u64`, or `i64`
+    /// as a *character* value wrapped in [`Some`],
+    /// and a `bool` or `null` value as [`None`].
+    ///
+    /// A string is accepted if it contains exactly one character, either
+    /// as-is or after trimming any leading and trailing whitespace. An
+    /// integer is treated as a Unicode *code point*.
+    ///
+    /// # Returns
+    /// A [`Some`] with the character (`char`) value of a string
+    /// or code point.
+    ///
+    /// A [`None`] in the case of:
+    ///   * a `bool` value.
+    ///   * a `null` value.
+    ///   * any *de-serialization* errors.
+    ///     * ex. a string is empty or not a single character.
+    ///     * ex. an integer is not a valid Unicode scalar value.
+    ///
+    pub fn as_opt_char, DeserializeOptionalCharWithVisitor;
+
+    expecting = "a single-character string or a code point";
+    hint = deserialize_char;
+
+    value {
+        i64(v) => match coerce::i64_to_char(v) {
+            Ok(c) => Ok(observed!("as_char", I64, Lossless, c)),
+            Err(_) => Err(de::Error::invalid_value(
+                Unexpected::Signed(v),
+                &DeserializeCharWithVisitor,
+            )),
+        },
+        u64(v) => match coerce::u64_to_char(v) {
+            Ok(c) => Ok(observed!("as_char", U64, Lossless, c)),
+            Err(_) => Err(de::Error::invalid_value(
+                Unexpected::Unsigned(v),
+                &DeserializeCharWithVisitor,
+            )),
+        },
+        char(v) => Ok(observed!("as_char", Char, Lossless, v)),
+        str(v) => match coerce::str_to_char(v) {
+            Ok(c) => Ok(observed!(
+                "as_char",
+                Str,
+                if c.len_utf8() == v.len() { Lossless } else { Lossy },
+                c
+            )),
+            Err(CoerceError::Empty) => Ok(observed!("as_char", Str, Defaulted, '\0')),
+            Err(_) => Err(de::Error::invalid_value(Unexpected::Str(v), &DeserializeCharWithVisitor)),
+        },
+        // We encounter a `null` value; this default implementation returns a
+        // "null" character.
+        unit => Ok(observed!("as_char", Null, Defaulted, '\0')),
+    }
+
+    option {
+        bool(_) => observed!("as_opt_char", Bool, Dropped, None),
+        i64(v) => observed!(
+            "as_opt_char",
+            I64,
+            o = coerce::i64_to_char(v).ok(),
+            if o.is_some() { Lossless } else { Dropped }
+        ),
+        u64(v) => observed!(
+            "as_opt_char",
+            U64,
+            o = coerce::u64_to_char(v).ok(),
+            if o.is_some() { Lossless } else { Dropped }
+        ),
+        f64(_) => observed!("as_opt_char", F64, Dropped, None),
+        char(v) => observed!("as_opt_char", Char, Lossless, Some(v)),
+        str(v) => match coerce::str_to_char(v) {
+            Ok(c) => observed!(
+                "as_opt_char",
+                Str,
+                if c.len_utf8() == v.len() { Lossless } else { Lossy },
+                Some(c)
+            ),
+            Err(CoerceError::Empty) => observed!("as_opt_char", Str, Defaulted, None),
+            Err(_) => observed!("as_opt_char", Str, Dropped, None),
+        },
+        // We encounter a `null` value; this default implementation returns an
+        // `Option::None` value.
+        unit => observed!("as_opt_char", Null, Defaulted, None),
+    }
+}
+
+/// De-serialize either a `null`, `str`, `bool`, `i64`, `f64`, or `u64`
+/// as a (possibly borrowed) *string* value.
+///
+/// When the input is a string borrowed from the original data (as with
+/// `serde_json::from_str`), the result borrows from it and no allocation
+/// takes place. Strings that need unescaping, as well as stringified
+/// booleans and numbers, result in an owned value.
+///
+/// > Note: the field needs to be annotated with `#[serde(borrow)]`, so that
+/// > the derived implementation ties the field's lifetime to the input.
+///
+/// ```rust
+/// use std::borrow::Cow;
+///
+/// use serde::Deserialize;
+/// use serde_this_or_that::as_cow_str;
+///
+/// #[derive(Deserialize)]
+/// struct Msg<'a> {
+///     #[serde(borrow, deserialize_with = "as_cow_str")]
+///     name: Cow<'a, str>,
+/// }
+///
+/// let m: Msg<'_> = serde_json::from_str(r#"{"name": "hello"}"#).unwrap();
+/// assert!(matches!(m.name, Cow::Borrowed("hello")));
+///
+/// let m: Msg<'_> = serde_json::from_str(r#"{"name": 123}"#).unwrap();
+/// assert!(matches!(m.name, Cow::Owned(ref s) if s == "123"));
+/// ```
+///
+/// # Returns
+/// The `Cow<str>` value of a string, boolean, or number.
+///
+#[cfg(feature = "alloc")]
+pub fn as_cow_str<'de, D>(deserializer: D) -> Result<Cow<'de, str>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if is_typed(&deserializer) {
+        deserializer.deserialize_str(DeserializeCowStrWithVisitor)
+    } else {
+        deserializer.deserialize_any(DeserializeCowStrWithVisitor)
+    }
+}
+
+/// De-serialize either a `null` or a borrowed `str` as a *string slice*.
+///
+/// Unlike [`as_cow_str`], this never allocates; as such, it only succeeds
+/// when the input is a string that can be borrowed directly from the
+/// original data.
+///
+/// > Note: the field needs to be annotated with `#[serde(borrow)]`, so that
+/// > the derived implementation ties the field's lifetime to the input.
 ///
 /// # Errors
-/// Returns an error if a string is non-empty and not a valid numeric
-/// value, or if the unsigned value `u64` *overflows* when converted
-/// to `i64`.
+/// Returns an error if the value is a boolean or number, or a string which
+/// cannot be borrowed (for example, one containing escape sequences).
 ///
 /// # Returns
-/// The signed (`i64`) value of a string or number.
+/// The `&str` value of a borrowed string. A `null` value results in an
+/// empty string.
 ///
-pub fn as_i64<'de, D>(deserializer: D) -> Result<i64, D::Error>
+pub fn as_str_ref<'de, D>(deserializer: D) -> Result<&'de str, D::Error>
 where
     D: Deserializer<'de>,
 {
-    deserializer.deserialize_any(DeserializeI64WithVisitor)
+    if is_typed(&deserializer) {
+        deserializer.deserialize_str(DeserializeStrRefWithVisitor)
+    } else {
+        deserializer.deserialize_any(DeserializeStrRefWithVisitor)
+    }
 }
 
-/// De-serialize either a `null`, `str`, `u64`, `f64`, or `i64`
-/// as an *unsigned* value.
+/// De-serialize a `str` as an (owned) *string* value, exactly as it appears
+/// in the input.
+///
+/// Unlike [`as_string`], this requests a string from the de-serializer with
+/// [`deserialize_string`](Deserializer::deserialize_string), rather than
+/// accepting whichever type it infers. This matters for formats such as CSV,
+/// where every value is text and `deserialize_any` guesses at its type: with
+/// [`as_string`], a ZIP code `02134` is read as the number `2134`, and a
+/// price `1.50` as `1.5`, while this helper keeps the text of the cell.
+///
+/// ```rust
+/// use serde::Deserialize;
+/// use serde_this_or_that::{as_raw_string, as_string};
+///
+/// #[derive(Deserialize)]
+/// struct Row {
+///     #[serde(deserialize_with = "as_raw_string")]
+///     zip: String,
+///     #[serde(deserialize_with = "as_string")]
+///     price: String,
+/// }
+///
+/// let mut reader = csv::Reader::from_reader("zip,price\n02134,1.50\n".as_bytes());
+/// let row: Row = reader.deserialize().next().unwrap().unwrap();
+/// assert_eq!(row.zip, "02134");
+/// assert_eq!(row.price, "1.5");
+/// ```
 ///
 /// # Errors
-/// Returns an error if a string is non-empty and not a valid numeric
-/// value, or if the signed value `i64` represents a *negative* number.
+/// Returns an error if the de-serializer cannot provide a string; for
+/// example, for a JSON number or `null`. Use [`as_string`] for formats
+/// which keep track of the type of each value.
 ///
 /// # Returns
-/// The unsigned (`u64`) value of a string or number.
+/// The owned `String` value of a string.
 ///
-pub fn as_u64<'de, D>(deserializer: D) -> Result<u64, D::Error>
+#[cfg(feature = "alloc")]
+pub fn as_raw_string<'de, D>(deserializer: D) -> Result<String, D::Error>
 where
     D: Deserializer<'de>,
 {
-    deserializer.deserialize_any(DeserializeU64WithVisitor)
+    deserializer.deserialize_string(DeserializeRawStringWithVisitor)
 }
 
-/// De-serialize either a `null`, `str`, `f64`, `u64`, or `i64`
-/// as a *float* value.
+/// De-serialize a `str` as an (owned) *string* value wrapped in [`Some`],
+/// exactly as it appears in the input, and a `null` value (or an empty CSV
+/// cell) as [`None`].
+///
+/// See [`as_raw_string`] for when to use this rather than
+/// [`as_opt_string`].
 ///
 /// # Errors
-/// Returns an error if a string is non-empty and not a valid numeric value.
+/// Returns an error if the value is neither `null` nor a string.
 ///
 /// # Returns
-/// The floating point (`f64`) value of a string or number.
+/// A [`Some`] with the owned `String` value of a string.
 ///
-pub fn as_f64<'de, D>(deserializer: D) -> Result<f64, D::Error>
+/// A [`None`] in the case of a `null` value.
+///
+#[cfg(feature = "alloc")]
+pub fn as_opt_raw_string<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    deserializer.deserialize_any(DeserializeF64WithVisitor)
+    deserializer.deserialize_option(DeserializeOptionalRawStringWithVisitor)
+}
+
+/// How [`as_char_with`] and [`as_opt_char_with`] read a string.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CharMode {
+    /// Accept a string with exactly one character, either as-is or after
+    /// trimming any leading and trailing whitespace, as [`as_char`] does.
+    #[default]
+    Trimmed,
+    /// Only accept a string with exactly one character as-is, so that
+    /// `" Y "` is an error (or [`None`], for [`as_opt_char_with`]).
+    Exact,
 }
 
-/// De-serialize either a `null`, `bool`, `str`, `u64`, or `f64`
-/// as a *boolean* value.
+/// Same as [`as_char`], but reads a string as set by the `mode`.
 ///
-/// # Truthy String Values
-/// > Note: the pattern matching is *case insensitive*, so `YES` or `yes`
-/// > works just the same.
+/// To use this with `deserialize_with`, wrap it in a function of your own:
 ///
-/// These are the following "truthy" string values that result in a
-/// boolean value of `true`:
+/// ```rust
+/// use serde::{Deserialize, Deserializer};
+/// use serde_this_or_that::{as_char_with, CharMode};
 ///
-///   - `1`
-///   - `OK`
-///   - `ON`
-///   - `T`
-///   - `TRUE`
-///   - `Y`
-///   - `YES`
+/// fn as_exact_char<'de, D>(deserializer: D) -> Result<char, D::Error>
+/// where
+///     D: Deserializer<'de>,
+/// {
+///     as_char_with(deserializer, CharMode::Exact)
+/// }
 ///
-/// # Errors
-/// Returns an error if an unsigned `u64` or a float `f64` value is not
-/// a *zero* or a *one*.
+/// #[derive(Deserialize)]
+/// struct Msg {
+///     #[serde(deserialize_with = "as_exact_char")]
+///     initial: char,
+/// }
 ///
-/// # Returns
-/// The boolean (`bool`) value of a string, boolean, or number.
+/// let m: Msg = serde_json::from_str(r#"{"initial": "Y"}"#).unwrap();
+/// assert_eq!(m.initial, 'Y');
 ///
-pub fn as_bool<'de, D>(deserializer: D) -> Result<bool, D::Error>
+/// assert!(serde_json::from_str::<Msg>(r#"{"initial": " Y "}"#).is_err());
+/// ```
+///
+/// # Errors
+/// Returns an error if a string is non-empty and not a single character
+/// (as set by the `mode`), or if an integer is not a valid Unicode scalar
+/// value.
+///
+pub fn as_char_with<'de, D>(deserializer: D, mode: CharMode) -> Result<char, D::Error>
 where
     D: Deserializer<'de>,
 {
-    deserializer.deserialize_any(DeserializeBoolWithVisitor)
+    match mode {
+        CharMode::Trimmed => as_char(deserializer),
+        CharMode::Exact if is_typed(&deserializer) => {
+            deserializer.deserialize_char(ExactCharVisitor(DeserializeCharWithVisitor, true))
+        }
+        CharMode::Exact => {
+            deserializer.deserialize_any(ExactCharVisitor(DeserializeCharWithVisitor, false))
+        }
+    }
 }
 
-/// De-serialize either a `null`, `str`, `bool`, `i64`, `f64`, or `u64`
-/// as an (owned) *string* value.
+/// Same as [`as_opt_char`], but reads a string as set by the `mode`.
 ///
 /// # Returns
-/// The owned `String` value of a string, boolean, or number.
+/// A [`Some`] with the character (`char`) value of a string or code point,
+/// or a [`None`] as for [`as_opt_char`].
 ///
-pub fn as_string<'de, D>(deserializer: D) -> Result<String, D::Error>
+pub fn as_opt_char_with<'de, D>(deserializer: D, mode: CharMode) -> Result<Option<char>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    deserializer.deserialize_any(DeserializeStringWithVisitor)
+    match mode {
+        CharMode::Trimmed => as_opt_char(deserializer),
+        CharMode::Exact if is_typed(&deserializer) => deserializer
+            .deserialize_option(ExactCharVisitor(DeserializeOptionalCharWithVisitor, true)),
+        CharMode::Exact => deserializer
+            .deserialize_any(ExactCharVisitor(DeserializeOptionalCharWithVisitor, false)),
+    }
 }
 
-// TODO maybe update these definitions into a macro ..?
+/// A visitor for [`as_char`] or [`as_opt_char`] which rejects a string that
+/// only has a single character after it is trimmed.
+trait RejectStr<'de>: de::Visitor<'de> {
+    /// Returns the result for a string `v` which is not a single character.
+    fn reject<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error;
+}
 
-struct DeserializeU64WithVisitor;
+impl RejectStr<'_> for DeserializeCharWithVisitor {
+    fn reject<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Err(de::Error::invalid_value(Unexpected::Str(v), &self))
+    }
+}
 
-impl de::Visitor<'_> for DeserializeU64WithVisitor {
-    type Value = u64;
+impl RejectStr<'_> for DeserializeOptionalCharWithVisitor {
+    fn reject<E>(self, _: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(observed!("as_opt_char", Str, Dropped, None))
+    }
+}
+
+/// A visitor which passes a value on to the inner visitor, but does not
+/// trim a string, as set by [`CharMode::Exact`]. The flag is set if the
+/// value inside of a `Some` is requested as a `char` rather than with
+/// `deserialize_any`, for the `typed` feature.
+struct ExactCharVisitor<V>(V, bool);
+
+impl<'de, V> de::Visitor<'de> for ExactCharVisitor<V>
+where
+    V: RejectStr<'de>,
+{
+    type Value = V::Value;
 
     fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-        formatter.write_str("an unsigned integer or a string")
+        self.0.expecting(formatter)
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_bool(v)
     }
 
     fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        match u64::try_from(v) {
-            Ok(v) => Ok(v),
-            Err(_) => Err(E::custom(format!(
-                "overflow: Unable to convert signed value `{v:?}` to u64"
-            ))),
-        }
+        self.0.visit_i64(v)
     }
 
     fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Ok(v)
+        self.0.visit_u64(v)
     }
 
     fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Ok(v.round() as u64)
+        self.0.visit_f64(v)
+    }
+
+    fn visit_char<E>(self, v: char) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_char(v)
     }
 
     fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        if let Ok(n) = v.parse::<u64>() {
-            Ok(n)
-        } else if v.is_empty() {
-            Ok(0)
-        } else if let Ok(f) = v.parse::<f64>() {
-            Ok(f.round() as u64)
-        } else {
-            Err(E::invalid_value(Unexpected::Str(v), &self))
+        match coerce::str_to_char_exact(v) {
+            Err(CoerceError::Invalid) => self.0.reject(v),
+            _ => self.0.visit_str(v),
         }
     }
 
-    /// We encounter a `null` value; this default implementation returns a
-    /// "zero" value.
     fn visit_unit<E>(self) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Ok(0)
+        self.0.visit_unit()
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_none()
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if self.1 {
+            deserializer.deserialize_char(self)
+        } else {
+            deserializer.deserialize_any(self)
+        }
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.visit_some(deserializer)
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        crate::de_wrapped::visit_wrapped_map(map, self)
     }
 }
 
-struct DeserializeI64WithVisitor;
+/// How [`as_string_with`] and [`as_opt_string_with`] read a value.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum StringMode {
+    /// Accept whichever type the de-serializer infers, as [`as_string`]
+    /// does; a number is written in its shortest form, such as `1.5` for
+    /// a CSV cell `1.50`.
+    #[default]
+    Inferred,
+    /// Request a string with
+    /// [`deserialize_string`](Deserializer::deserialize_string), as
+    /// [`as_raw_string`] does, so that the text of a CSV cell is kept as-is.
+    /// Unlike [`as_raw_string`], whatever the de-serializer hands over for
+    /// the request (such as a `null` value, or a number) is still coerced as
+    /// with [`as_string`], rather than being an error.
+    Raw,
+}
 
-impl de::Visitor<'_> for DeserializeI64WithVisitor {
-    type Value = i64;
+/// Same as [`as_string`], but reads the value as set by the `mode`.
+///
+/// To use this with `deserialize_with`, wrap it in a function of your own:
+///
+/// ```rust
+/// use serde::{Deserialize, Deserializer};
+/// use serde_this_or_that::{as_string_with, StringMode};
+///
+/// fn as_cell<'de, D>(deserializer: D) -> Result<String, D::Error>
+/// where
+///     D: Deserializer<'de>,
+/// {
+///     as_string_with(deserializer, StringMode::Raw)
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Row {
+///     #[serde(deserialize_with = "as_cell")]
+///     zip: String,
+/// }
+///
+/// let mut reader = csv::Reader::from_reader("zip\n02134\n".as_bytes());
+/// let row: Row = reader.deserialize().next().unwrap().unwrap();
+/// assert_eq!(row.zip, "02134");
+/// ```
+///
+/// # Errors
+/// Returns an error if the de-serializer cannot provide a value which
+/// [`as_string`] accepts.
+///
+#[cfg(feature = "alloc")]
+pub fn as_string_with<'de, D>(deserializer: D, mode: StringMode) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match mode {
+        StringMode::Inferred => as_string(deserializer),
+        StringMode::Raw => {
+            deserializer.deserialize_string(RawVisitor(DeserializeStringWithVisitor))
+        }
+    }
+}
+
+/// Same as [`as_opt_string`], but reads the value as set by the `mode`.
+///
+/// With [`StringMode::Raw`], an empty CSV cell results in [`None`], and the
+/// text of any other cell is kept as-is.
+///
+/// # Returns
+/// A [`Some`] with the owned `String` value of a string, boolean, or
+/// number, or a [`None`] as for [`as_opt_string`].
+///
+#[cfg(feature = "alloc")]
+pub fn as_opt_string_with<'de, D>(
+    deserializer: D,
+    mode: StringMode,
+) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match mode {
+        StringMode::Inferred => as_opt_string(deserializer),
+        StringMode::Raw => {
+            deserializer.deserialize_option(RawVisitor(DeserializeOptionalStringWithVisitor))
+        }
+    }
+}
+
+/// A visitor which passes a value on to the inner visitor, and requests a
+/// string (rather than calling `deserialize_any`) for the value inside of a
+/// `Some` or a newtype struct.
+#[cfg(feature = "alloc")]
+struct RawVisitor<V>(V);
+
+#[cfg(feature = "alloc")]
+impl<'de, V> de::Visitor<'de> for RawVisitor<V>
+where
+    V: de::Visitor<'de>,
+{
+    type Value = V::Value;
 
     fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-        formatter.write_str("a signed integer or a string")
+        self.0.expecting(formatter)
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_bool(v)
     }
 
     fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Ok(v)
+        self.0.visit_i64(v)
     }
 
     fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        match i64::try_from(v) {
-            Ok(v) => Ok(v),
-            Err(_) => Err(E::custom(format!(
-                "overflow: Unable to convert unsigned value `{v:?}` to i64"
-            ))),
-        }
+        self.0.visit_u64(v)
     }
 
     fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Ok(v.round() as i64)
+        self.0.visit_f64(v)
     }
 
     fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        if let Ok(n) = v.parse::<i64>() {
-            Ok(n)
-        } else if v.is_empty() {
-            Ok(0)
-        } else if let Ok(f) = v.parse::<f64>() {
-            Ok(f.round() as i64)
-        } else {
-            Err(E::invalid_value(Unexpected::Str(v), &self))
-        }
+        self.0.visit_str(v)
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_string(v)
     }
 
-    /// We encounter a `null` value; this default implementation returns a
-    /// "zero" value.
     fn visit_unit<E>(self) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Ok(0)
+        self.0.visit_unit()
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_none()
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_string(self)
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_string(self)
     }
 }
 
-struct DeserializeF64WithVisitor;
+/// The [`Visitor`](de::Visitor) used by [`as_cow_str`].
+#[cfg(feature = "alloc")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DeserializeCowStrWithVisitor;
 
-impl de::Visitor<'_> for DeserializeF64WithVisitor {
-    type Value = f64;
+#[cfg(feature = "alloc")]
+impl<'de> de::Visitor<'de> for DeserializeCowStrWithVisitor {
+    type Value = Cow<'de, str>;
 
     fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-        formatter.write_str("a float or a string")
+        formatter.write_str("a string, bool, or a number")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(observed!(
+            "as_cow_str",
+            Bool,
+            Lossless,
+            Cow::Borrowed(if v { "true" } else { "false" })
+        ))
     }
 
     fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Ok(v as f64)
+        Ok(observed!(
+            "as_cow_str",
+            I64,
+            Lossless,
+            Cow::Owned(v.to_string())
+        ))
     }
 
     fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Ok(v as f64)
+        Ok(observed!(
+            "as_cow_str",
+            U64,
+            Lossless,
+            Cow::Owned(v.to_string())
+        ))
     }
 
     fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Ok(v)
+        Ok(observed!(
+            "as_cow_str",
+            F64,
+            Lossless,
+            Cow::Owned(v.to_string())
+        ))
+    }
+
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(observed!("as_cow_str", Str, Lossless, Cow::Borrowed(v)))
     }
 
     fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        if let Ok(f) = v.parse::<f64>() {
-            Ok(f)
-        } else if v.is_empty() {
-            Ok(0.0)
-        } else {
-            Err(E::invalid_value(Unexpected::Str(v), &self))
-        }
+        Ok(observed!(
+            "as_cow_str",
+            Str,
+            Lossless,
+            Cow::Owned(v.to_owned())
+        ))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(observed!("as_cow_str", Str, Lossless, Cow::Owned(v)))
     }
 
-    /// We encounter a `null` value; this default implementation returns a
-    /// "zero" value.
+    /// We encounter a `null` value; this default implementation returns an
+    /// "empty" string.
     fn visit_unit<E>(self) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Ok(0.0)
+        Ok(observed!("as_cow_str", Null, Defaulted, Cow::Borrowed("")))
     }
+    crate::__define_coercion_wrappers!(deserialize_str);
 }
 
-struct DeserializeBoolWithVisitor;
+/// The [`Visitor`](de::Visitor) used by [`as_str_ref`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DeserializeStrRefWithVisitor;
 
-impl de::Visitor<'_> for DeserializeBoolWithVisitor {
-    type Value = bool;
+impl<'de> de::Visitor<'de> for DeserializeStrRefWithVisitor {
+    type Value = &'de str;
 
     fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-        formatter.write_str("an integer (0 or 1) or a string")
+        formatter.write_str("a borrowed string")
     }
 
-    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Ok(v)
+        Ok(observed!("as_str_ref", Str, Lossless, v))
     }
 
-    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    /// We encounter a `null` value; this default implementation returns an
+    /// "empty" string.
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Err(de::Error::invalid_value(
-            Unexpected::Signed(v),
-            &"zero or one",
-        ))
+        Ok(observed!("as_str_ref", Null, Defaulted, ""))
     }
+    crate::__define_coercion_wrappers!(deserialize_str);
+}
 
-    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+/// The [`Visitor`](de::Visitor) used by [`as_raw_string`].
+#[cfg(feature = "alloc")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DeserializeRawStringWithVisitor;
+
+#[cfg(feature = "alloc")]
+impl<'de> de::Visitor<'de> for DeserializeRawStringWithVisitor {
+    type Value = String;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a string")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        match v {
-            0 => Ok(false),
-            1 => Ok(true),
-            other => Err(de::Error::invalid_value(
-                Unexpected::Unsigned(other),
-                &"zero or one",
-            )),
-        }
+        Ok(observed!("as_raw_string", Str, Lossless, v.to_owned()))
     }
 
-    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        match v as u8 {
-            0 => Ok(false),
-            1 => Ok(true),
-            _ => Err(de::Error::invalid_value(
-                Unexpected::Float(v),
-                &"zero or one",
-            )),
-        }
+        Ok(observed!("as_raw_string", Str, Lossless, v))
     }
 
-    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
     where
-        E: de::Error,
+        D: Deserializer<'de>,
     {
-        // First, try to match common true/false phrases *without*
-        // using `to_uppercase()`. This approach is likely more efficient.
-        match v {
-            "t" | "T" | "true" | "True" | "1" => Ok(true),
-            "f" | "F" | "false" | "False" | "0" => Ok(false),
-            other => {
-                // So from the above, we've already matched the following
-                // "truthy" phrases: ["T", "1"].
-                // To be completely thorough, we also need to do a case-
-                // insensitive match on ["OK", "ON", "TRUE", "Y", "YES"].
-                match other.to_uppercase().as_str() {
-                    "OK" | "ON" | "TRUE" | "Y" | "YES" => Ok(true),
-                    _ => Ok(false),
-                }
-            }
-        }
+        deserializer.deserialize_string(self)
     }
 
-    /// We encounter a `null` value; this default implementation returns a
-    /// "false" value.
-    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
     where
-        E: de::Error,
+        D: Deserializer<'de>,
     {
-        Ok(false)
+        deserializer.deserialize_string(self)
     }
 }
 
-struct DeserializeStringWithVisitor;
+/// The [`Visitor`](de::Visitor) used by [`as_opt_raw_string`].
+#[cfg(feature = "alloc")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DeserializeOptionalRawStringWithVisitor;
 
-impl de::Visitor<'_> for DeserializeStringWithVisitor {
-    type Value = String;
+#[cfg(feature = "alloc")]
+impl<'de> de::Visitor<'de> for DeserializeOptionalRawStringWithVisitor {
+    type Value = Option<String>;
 
     fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-        formatter.write_str("a string, bool, or a number")
+        formatter.write_str("a string")
     }
 
-    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Ok(v.to_string())
+        Ok(observed!(
+            "as_opt_raw_string",
+            Str,
+            Lossless,
+            Some(v.to_owned())
+        ))
     }
 
-    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Ok(v.to_string())
+        Ok(observed!("as_opt_raw_string", Str, Lossless, Some(v)))
     }
 
-    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    /// We encounter a `null` value; this default implementation returns an
+    /// `Option::None` value.
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Ok(v.to_string())
+        Ok(observed!("as_opt_raw_string", Null, Defaulted, None))
     }
 
-    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    fn visit_none<E>(self) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        Ok(v.to_string())
+        self.visit_unit()
     }
 
-    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
     where
-        E: de::Error,
+        D: Deserializer<'de>,
     {
-        Ok(v.to_owned())
+        deserializer.deserialize_string(self)
     }
 
-    /// We encounter a `null` value; this default implementation returns an
-    /// "empty" string.
-    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
     where
-        E: de::Error,
+        D: Deserializer<'de>,
     {
-        Ok(String::new())
+        deserializer.deserialize_string(self)
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use serde::Deserialize;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[cfg(feature = "alloc")]
+    mod as_raw_string_tests {
+        use super::*;
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct TestRawStrStruct {
+            #[serde(deserialize_with = "as_raw_string")]
+            field: String,
+            #[serde(default, deserialize_with = "as_opt_raw_string")]
+            opt: Option<String>,
+        }
+
+        #[test]
+        fn test_as_raw_string_with_string() {
+            let json = r#"{"field": "0123", "opt": "1.50"}"#;
+            let deserialized: TestRawStrStruct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized.field, "0123");
+            assert_eq!(deserialized.opt.as_deref(), Some("1.50"));
+        }
+
+        #[test]
+        fn test_as_raw_string_with_null() {
+            let json = r#"{"field": "", "opt": null}"#;
+            let deserialized: TestRawStrStruct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized.opt, None);
+
+            let json = r#"{"field": null}"#;
+            assert!(serde_json::from_str::<TestRawStrStruct>(json).is_err());
+        }
+
+        #[test]
+        fn test_as_raw_string_with_number_is_an_error() {
+            for json in [r#"{"field": 123}"#, r#"{"field": "", "opt": 1.5}"#] {
+                let err = serde_json::from_str::<TestRawStrStruct>(json).unwrap_err();
+                assert!(err.to_string().contains("expected a string"), "{err}");
+            }
+        }
+
+        #[test]
+        fn test_as_string_with_mode() {
+            use serde::de::value::{Error, U64Deserializer, UnitDeserializer};
+
+            let json = r#""0123""#;
+            let mut de = serde_json::Deserializer::from_str(json);
+            assert_eq!(as_string_with(&mut de, StringMode::Raw).unwrap(), "0123");
+
+            // A value which is handed over for the request is still coerced.
+            let de = U64Deserializer::<Error>::new(7);
+            assert_eq!(as_string_with(de, StringMode::Raw).unwrap(), "7");
+            let de = UnitDeserializer::<Error>::new();
+            assert_eq!(as_string_with(de, StringMode::Raw).unwrap(), "");
+            let de = UnitDeserializer::<Error>::new();
+            assert_eq!(as_opt_string_with(de, StringMode::Raw).unwrap(), None);
+
+            let mut de = serde_json::Deserializer::from_str("1.50");
+            assert_eq!(
+                as_string_with(&mut de, StringMode::Inferred).unwrap(),
+                "1.5"
+            );
+        }
+    }
 
+    #[cfg(feature = "alloc")]
     mod as_string_tests {
         use super::*;
 
@@ -488,6 +1483,88 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "alloc")]
+    mod as_cow_str_tests {
+        use super::*;
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct TestCowStrStruct<'a> {
+            #[serde(borrow, deserialize_with = "as_cow_str")]
+            field: Cow<'a, str>,
+        }
+
+        #[test]
+        fn test_as_cow_str_with_string_is_borrowed() {
+            let json = r#"{"field": "Hello"}"#;
+            let deserialized: TestCowStrStruct<'_> = serde_json::from_str(json).unwrap();
+            assert!(matches!(deserialized.field, Cow::Borrowed("Hello")));
+        }
+
+        #[test]
+        fn test_as_cow_str_with_escaped_string_is_owned() {
+            let json = r#"{"field": "Hello\nWorld"}"#;
+            let deserialized: TestCowStrStruct<'_> = serde_json::from_str(json).unwrap();
+            assert!(matches!(deserialized.field, Cow::Owned(ref s) if s == "Hello\nWorld"));
+        }
+
+        #[test]
+        fn test_as_cow_str_with_number() {
+            let json = r#"{"field": -123}"#;
+            let deserialized: TestCowStrStruct<'_> = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized.field, "-123");
+        }
+
+        #[test]
+        fn test_as_cow_str_with_boolean_and_null() {
+            let json = r#"{"field": false}"#;
+            let deserialized: TestCowStrStruct<'_> = serde_json::from_str(json).unwrap();
+            assert!(matches!(deserialized.field, Cow::Borrowed("false")));
+
+            let json = r#"{"field": null}"#;
+            let deserialized: TestCowStrStruct<'_> = serde_json::from_str(json).unwrap();
+            assert!(matches!(deserialized.field, Cow::Borrowed("")));
+        }
+
+        #[test]
+        fn test_as_cow_str_from_value_is_owned() {
+            let value = serde_json::json!({"field": "Hello"});
+            let deserialized = TestCowStrStruct::deserialize(value).unwrap();
+            assert!(matches!(deserialized.field, Cow::Owned(ref s) if s == "Hello"));
+        }
+    }
+
+    mod as_str_ref_tests {
+        use super::*;
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct TestStrRefStruct<'a> {
+            #[serde(borrow, deserialize_with = "as_str_ref")]
+            field: &'a str,
+        }
+
+        #[test]
+        fn test_as_str_ref_with_string() {
+            let json = r#"{"field": "Hello"}"#;
+            let deserialized: TestStrRefStruct<'_> = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized, TestStrRefStruct { field: "Hello" });
+        }
+
+        #[test]
+        fn test_as_str_ref_with_null() {
+            let json = r#"{"field": null}"#;
+            let deserialized: TestStrRefStruct<'_> = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized, TestStrRefStruct { field: "" });
+        }
+
+        #[test]
+        fn test_as_str_ref_with_unborrowable_values() {
+            for json in [r#"{"field": 123}"#, r#"{"field": "a\tb"}"#] {
+                let deserialized = serde_json::from_str::<TestStrRefStruct<'_>>(json);
+                assert!(deserialized.is_err(), "{json}");
+            }
+        }
+    }
+
     mod as_bool_tests {
         use super::*;
 
@@ -643,6 +1720,88 @@ mod tests {
         }
     }
 
+    mod as_char_tests {
+        use super::*;
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct TestCharStruct {
+            #[serde(deserialize_with = "as_char")]
+            field: char,
+        }
+
+        #[test]
+        fn test_as_char_with_string() {
+            let json = r#"{"field": "Y"}"#;
+            let deserialized: TestCharStruct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized, TestCharStruct { field: 'Y' });
+        }
+
+        #[test]
+        fn test_as_char_with_padded_string() {
+            let json = r#"{"field": "  N "}"#;
+            let deserialized: TestCharStruct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized, TestCharStruct { field: 'N' });
+        }
+
+        #[test]
+        fn test_as_char_with_whitespace_char() {
+            let json = r#"{"field": " "}"#;
+            let deserialized: TestCharStruct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized, TestCharStruct { field: ' ' });
+        }
+
+        #[test]
+        fn test_as_char_with_code_point() {
+            let json = r#"{"field": 89}"#;
+            let deserialized: TestCharStruct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized, TestCharStruct { field: 'Y' });
+        }
+
+        #[test]
+        fn test_as_char_with_null_and_empty_string() {
+            for json in [r#"{"field": null}"#, r#"{"field": ""}"#] {
+                let deserialized: TestCharStruct = serde_json::from_str(json).unwrap();
+                assert_eq!(deserialized, TestCharStruct { field: '\0' });
+            }
+        }
+
+        #[test]
+        fn test_as_char_with_invalid_values() {
+            for json in [
+                r#"{"field": "YES"}"#,
+                r#"{"field": -1}"#,
+                r#"{"field": 55296}"#,
+                r#"{"field": 4294967385}"#,
+            ] {
+                let deserialized = serde_json::from_str::<TestCharStruct>(json);
+                assert!(deserialized.is_err(), "{json}");
+            }
+        }
+
+        #[test]
+        fn test_as_char_with_mode() {
+            let mut de = serde_json::Deserializer::from_str(r#"" Y ""#);
+            assert_eq!(as_char_with(&mut de, CharMode::Trimmed).unwrap(), 'Y');
+
+            let mut de = serde_json::Deserializer::from_str(r#"" Y ""#);
+            assert!(as_char_with(&mut de, CharMode::Exact).is_err());
+            let mut de = serde_json::Deserializer::from_str(r#"" Y ""#);
+            assert_eq!(as_opt_char_with(&mut de, CharMode::Exact).unwrap(), None);
+
+            // A single character (even whitespace), a code point, or a
+            // `null` value are read as with `as_char`.
+            for (json, expected) in [(r#"" ""#, ' '), ("89", 'Y'), ("null", '\0')] {
+                let mut de = serde_json::Deserializer::from_str(json);
+                assert_eq!(as_char_with(&mut de, CharMode::Exact).unwrap(), expected);
+            }
+            let mut de = serde_json::Deserializer::from_str(r#""Y""#);
+            assert_eq!(
+                as_opt_char_with(&mut de, CharMode::Exact).unwrap(),
+                Some('Y')
+            );
+        }
+    }
+
     mod as_u64_tests {
         use super::*;
 
@@ -694,4 +1853,300 @@ mod tests {
             assert_eq!(deserialized, TestU64Struct { field: u64::MAX });
         }
     }
+
+    // Tests for as_opt_bool
+    mod as_opt_bool_tests {
+        use super::*;
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct TestOptBoolStruct {
+            #[serde(deserialize_with = "as_opt_bool")]
+            field: Option<bool>,
+        }
+
+        #[test]
+        fn test_as_opt_bool_with_truthy_values() {
+            let truthy_values = ["1", "OK", "ON", "T", "TRUE", "Y", "YES"];
+            for value in truthy_values {
+                let json = format!(r#"{{"field": "{}"}}"#, value);
+                let deserialized: TestOptBoolStruct = serde_json::from_str(&json).unwrap();
+                assert_eq!(deserialized, TestOptBoolStruct { field: Some(true) });
+            }
+        }
+
+        #[test]
+        fn test_as_opt_bool_with_falsy_values() {
+            let falsy_values = ["0", "OFF", "F", "FALSE", "N", "NO"];
+            for value in falsy_values {
+                let json = format!(r#"{{"field": "{}"}}"#, value);
+                let deserialized: TestOptBoolStruct = serde_json::from_str(&json).unwrap();
+                assert_eq!(deserialized, TestOptBoolStruct { field: Some(false) });
+            }
+        }
+
+        #[test]
+        fn test_as_opt_bool_with_null() {
+            let json = r#"{"field": null}"#;
+            let deserialized: TestOptBoolStruct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized, TestOptBoolStruct { field: None });
+        }
+
+        #[test]
+        fn test_as_opt_bool_with_invalid() {
+            let json = r#"{"field": "INVALID"}"#;
+            let deserialized: TestOptBoolStruct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized, TestOptBoolStruct { field: None });
+        }
+    }
+
+    // Tests for as_opt_char
+    mod as_opt_char_tests {
+        use super::*;
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct TestOptCharStruct {
+            #[serde(deserialize_with = "as_opt_char")]
+            field: Option<char>,
+        }
+
+        #[test]
+        fn test_as_opt_char_with_string() {
+            let json = r#"{"field": " Y"}"#;
+            let deserialized: TestOptCharStruct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized, TestOptCharStruct { field: Some('Y') });
+        }
+
+        #[test]
+        fn test_as_opt_char_with_code_point() {
+            let json = r#"{"field": 78}"#;
+            let deserialized: TestOptCharStruct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized, TestOptCharStruct { field: Some('N') });
+        }
+
+        #[test]
+        fn test_as_opt_char_with_null_and_empty_string() {
+            for json in [r#"{"field": null}"#, r#"{"field": ""}"#] {
+                let deserialized: TestOptCharStruct = serde_json::from_str(json).unwrap();
+                assert_eq!(deserialized, TestOptCharStruct { field: None });
+            }
+        }
+
+        #[test]
+        fn test_as_opt_char_with_invalid() {
+            for json in [r#"{"field": "YES"}"#, r#"{"field": 55296}"#] {
+                let deserialized: TestOptCharStruct = serde_json::from_str(json).unwrap();
+                assert_eq!(deserialized, TestOptCharStruct { field: None });
+            }
+        }
+    }
+
+    // Tests for as_opt_f64
+    mod as_opt_f64_tests {
+        use super::*;
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct TestOptF64Struct {
+            #[serde(deserialize_with = "as_opt_f64")]
+            field: Option<f64>,
+        }
+
+        #[test]
+        fn test_as_opt_f64_with_large_number() {
+            let json = r#"{"field": 1e308}"#;
+            let deserialized: TestOptF64Struct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized, TestOptF64Struct { field: Some(1e308) });
+        }
+
+        #[test]
+        fn test_as_opt_f64_with_negative_number() {
+            let json = r#"{"field": -123.45}"#;
+            let deserialized: TestOptF64Struct = serde_json::from_str(json).unwrap();
+            assert_eq!(
+                deserialized,
+                TestOptF64Struct {
+                    field: Some(-123.45)
+                }
+            );
+        }
+
+        #[test]
+        fn test_as_opt_f64_with_number() {
+            let json = r#"{"field": 123.45}"#;
+            let deserialized: TestOptF64Struct = serde_json::from_str(json).unwrap();
+            assert_eq!(
+                deserialized,
+                TestOptF64Struct {
+                    field: Some(123.45)
+                }
+            );
+        }
+
+        #[test]
+        fn test_as_opt_f64_with_integer() {
+            let json = r#"{"field": 123}"#;
+            let deserialized: TestOptF64Struct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized, TestOptF64Struct { field: Some(123.0) });
+        }
+
+        #[test]
+        fn test_as_opt_f64_with_null() {
+            let json = r#"{"field": null}"#;
+            let deserialized: TestOptF64Struct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized, TestOptF64Struct { field: None });
+        }
+
+        #[test]
+        fn test_as_opt_f64_with_invalid_string() {
+            let json = r#"{"field": "INVALID"}"#;
+            let deserialized: TestOptF64Struct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized, TestOptF64Struct { field: None });
+        }
+    }
+
+    // Tests for as_opt_i64
+    mod as_opt_i64_tests {
+        use super::*;
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct TestOptI64Struct {
+            #[serde(deserialize_with = "as_opt_i64")]
+            field: Option<i64>,
+        }
+
+        #[test]
+        fn test_as_opt_i64_with_integer() {
+            let json = r#"{"field": 123}"#;
+            let deserialized: TestOptI64Struct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized, TestOptI64Struct { field: Some(123) });
+        }
+
+        #[test]
+        fn test_as_opt_i64_with_string() {
+            let json = r#"{"field": "123"}"#;
+            let deserialized: TestOptI64Struct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized, TestOptI64Struct { field: Some(123) });
+        }
+
+        #[test]
+        fn test_as_opt_i64_with_float() {
+            let json = r#"{"field": 123.45}"#;
+            let deserialized: TestOptI64Struct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized, TestOptI64Struct { field: Some(123) });
+        }
+
+        #[test]
+        fn test_as_opt_i64_with_null() {
+            let json = r#"{"field": null}"#;
+            let deserialized: TestOptI64Struct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized, TestOptI64Struct { field: None });
+        }
+
+        #[test]
+        fn test_as_opt_i64_with_invalid() {
+            let json = r#"{"field": "INVALID"}"#;
+            let deserialized: TestOptI64Struct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized, TestOptI64Struct { field: None });
+        }
+    }
+
+    // Tests for as_opt_string
+    #[cfg(feature = "alloc")]
+    mod as_opt_string_tests {
+        use super::*;
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct TestOptStringStruct {
+            #[serde(deserialize_with = "as_opt_string")]
+            field: Option<String>,
+        }
+
+        #[test]
+        fn test_as_opt_string_with_string() {
+            let json = r#"{"field": "Hello"}"#;
+            let deserialized: TestOptStringStruct = serde_json::from_str(json).unwrap();
+            assert_eq!(
+                deserialized,
+                TestOptStringStruct {
+                    field: Some("Hello".to_owned())
+                }
+            );
+        }
+
+        #[test]
+        fn test_as_opt_string_with_number() {
+            let json = r#"{"field": 123}"#;
+            let deserialized: TestOptStringStruct = serde_json::from_str(json).unwrap();
+            assert_eq!(
+                deserialized,
+                TestOptStringStruct {
+                    field: Some("123".to_owned())
+                }
+            );
+        }
+
+        #[test]
+        fn test_as_opt_string_with_boolean() {
+            let json = r#"{"field": true}"#;
+            let deserialized: TestOptStringStruct = serde_json::from_str(json).unwrap();
+            assert_eq!(
+                deserialized,
+                TestOptStringStruct {
+                    field: Some("true".to_owned())
+                }
+            );
+        }
+
+        #[test]
+        fn test_as_opt_string_with_null() {
+            let json = r#"{"field": null}"#;
+            let deserialized: TestOptStringStruct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized, TestOptStringStruct { field: None });
+        }
+    }
+
+    // Tests for as_opt_u64
+    mod as_opt_u64_tests {
+        use super::*;
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct TestOptU64Struct {
+            #[serde(deserialize_with = "as_opt_u64")]
+            field: Option<u64>,
+        }
+
+        #[test]
+        fn test_as_opt_u64_with_integer() {
+            let json = r#"{"field": 123}"#;
+            let deserialized: TestOptU64Struct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized, TestOptU64Struct { field: Some(123) });
+        }
+
+        #[test]
+        fn test_as_opt_u64_with_string() {
+            let json = r#"{"field": "123"}"#;
+            let deserialized: TestOptU64Struct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized, TestOptU64Struct { field: Some(123) });
+        }
+
+        #[test]
+        fn test_as_opt_u64_with_float() {
+            let json = r#"{"field": 123.45}"#;
+            let deserialized: TestOptU64Struct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized, TestOptU64Struct { field: Some(123) });
+        }
+
+        #[test]
+        fn test_as_opt_u64_with_null() {
+            let json = r#"{"field": null}"#;
+            let deserialized: TestOptU64Struct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized, TestOptU64Struct { field: None });
+        }
+
+        #[test]
+        fn test_as_opt_u64_with_negative() {
+            let json = r#"{"field": -1}"#;
+            let deserialized = serde_json::from_str::<TestOptU64Struct>(json).unwrap();
+            assert_eq!(deserialized, TestOptU64Struct { field: None });
+        }
+    }
 }
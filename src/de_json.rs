@@ -0,0 +1,343 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::de::value::{MapAccessDeserializer, SeqAccessDeserializer};
+use crate::de::{
+    self, Deserialize, DeserializeOwned, Deserializer, IntoDeserializer, MapAccess, SeqAccess,
+};
+use crate::typed::is_typed;
+
+/// De-serialize either a `null`, inline JSON value, or a `str` containing
+/// JSON as a value of type `T`.
+///
+/// This handles payloads which are sometimes *double-encoded*, such as
+/// `"metadata": "{\"a\":1}"`, as well as the same payload sent directly,
+/// `"metadata": {"a":1}`.
+///
+/// ```rust
+/// use std::collections::HashMap;
+///
+/// use serde::Deserialize;
+/// use serde_this_or_that::as_json_str;
+///
+/// #[derive(Deserialize)]
+/// struct Event {
+///     #[serde(deserialize_with = "as_json_str")]
+///     metadata: HashMap<String, u64>,
+/// }
+///
+/// let e: Event = serde_json::from_str(r#"{"metadata": "{\"a\":1}"}"#).unwrap();
+/// assert_eq!(e.metadata["a"], 1);
+///
+/// let e: Event = serde_json::from_str(r#"{"metadata": {"a":1}}"#).unwrap();
+/// assert_eq!(e.metadata["a"], 1);
+/// ```
+///
+/// # Errors
+/// Returns an error if the inline value, or the JSON contained in a string,
+/// cannot be de-serialized as `T`. For an inline value, the error is
+/// reported at the position of the offending field.
+///
+/// # Returns
+/// The `T` value of an inline value or a string. A `null` value or an empty
+/// string is de-serialized as `T` from a JSON `null`, so this works as
+/// expected with an `Option` type.
+///
+pub fn as_json_str<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    if is_typed(&deserializer) {
+        return T::deserialize(deserializer);
+    }
+
+    deserializer.deserialize_any(DeserializeJsonStrWithVisitor(PhantomData))
+}
+
+/// De-serialize either an inline JSON value or a `str` containing JSON
+/// as a value of type `T` wrapped in [`Some`],
+/// and a `null` value as [`None`].
+///
+/// # Returns
+/// A [`Some`] with the `T` value of an inline value or a string.
+///
+/// A [`None`] in the case of:
+///   * a `null` value, or an empty string.
+///   * any *de-serialization* errors.
+///     * ex. a string does not contain valid JSON.
+///     * ex. the inline value, or the JSON in a string, is not a valid `T`.
+///
+pub fn as_opt_json_str<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    if is_typed(&deserializer) {
+        return Option::<T>::deserialize(deserializer);
+    }
+
+    // Buffer the value, so that an invalid `T` does not leave the input
+    // partially consumed.
+    let value = serde_json::Value::deserialize(deserializer)?;
+
+    Ok(match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(s) if s.is_empty() => None,
+        serde_json::Value::String(s) => serde_json::from_str(&s).ok(),
+        other => T::deserialize(other).ok(),
+    })
+}
+
+struct DeserializeJsonStrWithVisitor<T>(PhantomData<T>);
+
+impl<'de, T> de::Visitor<'de> for DeserializeJsonStrWithVisitor<T>
+where
+    T: DeserializeOwned,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a JSON value, or a string containing JSON")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        T::deserialize(v.into_deserializer())
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        T::deserialize(v.into_deserializer())
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        T::deserialize(v.into_deserializer())
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        T::deserialize(v.into_deserializer())
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if v.is_empty() {
+            self.visit_unit()
+        } else {
+            serde_json::from_str(v).map_err(|e| E::custom(format!("invalid JSON in string: {e}")))
+        }
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        T::deserialize(InlineDeserializer(SeqAccessDeserializer::new(seq)))
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        T::deserialize(InlineDeserializer(MapAccessDeserializer::new(map)))
+    }
+
+    /// We encounter a `null` value; this default implementation hands
+    /// a `null` over to `T`.
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        T::deserialize(().into_deserializer())
+    }
+}
+
+/// Wraps the de-serializer for an inline map or seq, so that it is treated
+/// as a [`Some`] value when `T` is an `Option`.
+struct InlineDeserializer<D>(D);
+
+impl<'de, D> Deserializer<'de> for InlineDeserializer<D>
+where
+    D: Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.0.deserialize_any(visitor)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_some(self.0)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.0.deserialize_enum(name, variants, visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Metadata {
+        a: u64,
+        tags: Vec<String>,
+    }
+
+    mod as_json_str_tests {
+        use super::*;
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct TestJsonStrStruct {
+            #[serde(deserialize_with = "as_json_str")]
+            field: Metadata,
+        }
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct TestJsonStrOptionStruct {
+            #[serde(deserialize_with = "as_json_str")]
+            field: Option<Vec<u64>>,
+        }
+
+        #[test]
+        fn test_as_json_str_with_string() {
+            let json = r#"{"field": "{\"a\": 1, \"tags\": [\"x\"]}"}"#;
+            let deserialized: TestJsonStrStruct = serde_json::from_str(json).unwrap();
+            assert_eq!(
+                deserialized.field,
+                Metadata {
+                    a: 1,
+                    tags: vec!["x".to_owned()]
+                }
+            );
+        }
+
+        #[test]
+        fn test_as_json_str_with_inline_map() {
+            let json = r#"{"field": {"a": 1, "tags": ["x"]}}"#;
+            let deserialized: TestJsonStrStruct = serde_json::from_str(json).unwrap();
+            assert_eq!(
+                deserialized.field,
+                Metadata {
+                    a: 1,
+                    tags: vec!["x".to_owned()]
+                }
+            );
+        }
+
+        #[test]
+        fn test_as_json_str_with_inline_seq_and_string() {
+            for json in [r#"{"field": [1, 2]}"#, r#"{"field": "[1, 2]"}"#] {
+                let deserialized: TestJsonStrOptionStruct = serde_json::from_str(json).unwrap();
+                assert_eq!(deserialized.field, Some(vec![1, 2]));
+            }
+        }
+
+        #[test]
+        fn test_as_json_str_with_null_and_empty_string() {
+            for json in [r#"{"field": null}"#, r#"{"field": ""}"#] {
+                let deserialized: TestJsonStrOptionStruct = serde_json::from_str(json).unwrap();
+                assert_eq!(deserialized.field, None);
+            }
+
+            let json = r#"{"field": null}"#;
+            assert!(serde_json::from_str::<TestJsonStrStruct>(json).is_err());
+        }
+
+        #[test]
+        fn test_as_json_str_error_location() {
+            // The error for an inline value points into the original input.
+            let json = r#"{"field": {"a": "one", "tags": []}}"#;
+            let err = serde_json::from_str::<TestJsonStrStruct>(json).unwrap_err();
+            assert_eq!((err.line(), err.column()), (1, 21));
+
+            let json = r#"{"field": "{\"a\": \"one\"}"}"#;
+            let err = serde_json::from_str::<TestJsonStrStruct>(json).unwrap_err();
+            assert!(err.to_string().contains("invalid JSON in string"), "{err}");
+        }
+    }
+
+    mod as_opt_json_str_tests {
+        use super::*;
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct TestOptJsonStrStruct {
+            #[serde(default, deserialize_with = "as_opt_json_str")]
+            field: Option<Metadata>,
+            #[serde(default)]
+            other: u64,
+        }
+
+        #[test]
+        fn test_as_opt_json_str_with_valid_values() {
+            for json in [
+                r#"{"field": "{\"a\": 1, \"tags\": []}"}"#,
+                r#"{"field": {"a": 1, "tags": []}}"#,
+            ] {
+                let deserialized: TestOptJsonStrStruct = serde_json::from_str(json).unwrap();
+                assert_eq!(deserialized.field, Some(Metadata { a: 1, tags: vec![] }));
+            }
+        }
+
+        #[test]
+        fn test_as_opt_json_str_with_null_missing_and_empty_string() {
+            for json in [r#"{"field": null}"#, r#"{"field": ""}"#, r#"{}"#] {
+                let deserialized: TestOptJsonStrStruct = serde_json::from_str(json).unwrap();
+                assert_eq!(deserialized.field, None);
+            }
+        }
+
+        #[test]
+        fn test_as_opt_json_str_with_invalid_values() {
+            for json in [
+                r#"{"field": "{not json", "other": 1}"#,
+                r#"{"field": {"a": "one", "tags": []}, "other": 1}"#,
+                r#"{"field": 123, "other": 1}"#,
+            ] {
+                let deserialized: TestOptJsonStrStruct = serde_json::from_str(json).unwrap();
+                assert_eq!(
+                    deserialized,
+                    TestOptJsonStrStruct {
+                        field: None,
+                        other: 1
+                    },
+                    "{json}"
+                );
+            }
+        }
+    }
+}
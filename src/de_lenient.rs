@@ -0,0 +1,714 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::de::value::{
+    BorrowedBytesDeserializer, BorrowedStrDeserializer, BytesDeserializer, MapAccessDeserializer,
+    SeqAccessDeserializer,
+};
+use crate::de::{
+    self, Deserialize, DeserializeSeed, Deserializer, EnumAccess, IntoDeserializer, MapAccess,
+    SeqAccess, VariantAccess, Visitor,
+};
+use crate::de_impl::as_cow_str;
+use crate::lenient::Lenient;
+use crate::typed::is_typed;
+
+/// A [`Deserializer`] which wraps another one, and applies the crate's rules
+/// to every value in the document.
+///
+/// Annotating a field with a helper such as [`as_u64`](crate::as_u64) only
+/// works for types which we own. For any other type (say, one from a
+/// generated SDK), de-serializing through a `LenientDeserializer` coerces
+/// each primitive the type asks for, such as a `u64` or a `bool`, with the
+/// same rules as the helper functions; this applies to the values inside of
+/// sequences, maps, options, and (externally tagged) enums as well.
+///
+/// ```rust
+/// use serde::Deserialize;
+/// use serde_this_or_that::LenientDeserializer;
+///
+/// // A type which we can't annotate.
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Config {
+///     retries: u64,
+///     enabled: bool,
+///     ratio: Option<f64>,
+///     tags: Vec<String>,
+/// }
+///
+/// let json = r#"{"retries": "3", "enabled": "yes", "ratio": "", "tags": [1, "b"]}"#;
+/// let mut de = serde_json::Deserializer::from_str(json);
+/// let config = Config::deserialize(LenientDeserializer::new(&mut de)).unwrap();
+///
+/// assert_eq!(
+///     config,
+///     Config {
+///         retries: 3,
+///         enabled: true,
+///         ratio: None,
+///         tags: vec!["1".into(), "b".into()],
+///     }
+/// );
+/// ```
+///
+/// An `Option<T>` is [`None`] for a `null` value or an empty string, and
+/// otherwise the value is de-serialized as a `T`, so that an invalid value
+/// is still an error (unlike with the `as_opt` helpers, which can't tell
+/// whether a value is optional).
+///
+/// Integers of 128 bits, byte arrays, and unit values are passed through to
+/// the inner deserializer as they are.
+///
+/// Untagged and internally tagged enums, as well as structs with a
+/// `#[serde(flatten)]` field, are not coerced: serde buffers their content
+/// with `deserialize_any` before it knows the type of each field, and then
+/// reads the fields from that buffer rather than from this deserializer. The
+/// fields of such a type need a helper, or a [`Lenient`](crate::Lenient)
+/// type, instead:
+///
+/// ```rust
+/// use serde::Deserialize;
+/// use serde_this_or_that::{Lenient, LenientDeserializer};
+///
+/// #[derive(Debug, Deserialize)]
+/// struct Page {
+///     size: u64,
+/// }
+///
+/// #[derive(Debug, Deserialize)]
+/// struct Query {
+///     #[serde(flatten)]
+///     page: Page,
+///     retries: u64,
+/// }
+///
+/// let json = r#"{"size": "10", "retries": "3"}"#;
+/// let mut de = serde_json::Deserializer::from_str(json);
+/// assert!(Query::deserialize(LenientDeserializer::new(&mut de)).is_err());
+///
+/// #[derive(Debug, Deserialize)]
+/// struct LenientPage {
+///     size: Lenient<u64>,
+/// }
+///
+/// #[derive(Debug, Deserialize)]
+/// struct LenientQuery {
+///     #[serde(flatten)]
+///     page: LenientPage,
+///     retries: Lenient<u64>,
+/// }
+///
+/// let mut de = serde_json::Deserializer::from_str(json);
+/// let query = LenientQuery::deserialize(LenientDeserializer::new(&mut de)).unwrap();
+/// assert_eq!((*query.page.size, *query.retries), (10, 3));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct LenientDeserializer<D> {
+    inner: D,
+}
+
+impl<D> LenientDeserializer<D> {
+    /// Wraps the `inner` deserializer.
+    pub const fn new(inner: D) -> Self {
+        Self { inner }
+    }
+
+    /// Returns the inner deserializer.
+    pub fn into_inner(self) -> D {
+        self.inner
+    }
+}
+
+/// Defines `deserialize_*` methods which coerce the value with the rules
+/// for `Lenient<$ty>`.
+macro_rules! deserialize_lenient {
+    ($($method:ident => $ty:ty, $visit:ident;)*) => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                let Lenient(v) = Lenient::<$ty>::deserialize(self.inner)?;
+                visitor.$visit(v)
+            }
+        )*
+    };
+}
+
+/// Defines `deserialize_*` methods which pass the request on to the inner
+/// deserializer, with the visitor wrapped in a [`LenientVisitor`].
+macro_rules! deserialize_forward {
+    ($($method:ident($($arg:ident: $arg_ty:ty),*);)*) => {
+        $(
+            fn $method<V>(self, $($arg: $arg_ty,)* visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                self.inner.$method($($arg,)* LenientVisitor(visitor))
+            }
+        )*
+    };
+}
+
+impl<'de, D> Deserializer<'de> for LenientDeserializer<D>
+where
+    D: Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    deserialize_lenient! {
+        deserialize_bool => bool, visit_bool;
+        deserialize_i8 => i8, visit_i8;
+        deserialize_i16 => i16, visit_i16;
+        deserialize_i32 => i32, visit_i32;
+        deserialize_i64 => i64, visit_i64;
+        deserialize_u8 => u8, visit_u8;
+        deserialize_u16 => u16, visit_u16;
+        deserialize_u32 => u32, visit_u32;
+        deserialize_u64 => u64, visit_u64;
+        deserialize_f32 => f32, visit_f32;
+        deserialize_f64 => f64, visit_f64;
+        deserialize_char => char, visit_char;
+        deserialize_string => String, visit_string;
+    }
+
+    deserialize_forward! {
+        deserialize_any();
+        deserialize_i128();
+        deserialize_u128();
+        deserialize_bytes();
+        deserialize_byte_buf();
+        deserialize_unit();
+        deserialize_unit_struct(name: &'static str);
+        deserialize_newtype_struct(name: &'static str);
+        deserialize_seq();
+        deserialize_tuple(len: usize);
+        deserialize_tuple_struct(name: &'static str, len: usize);
+        deserialize_map();
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+        deserialize_identifier();
+    }
+
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match as_cow_str(self.inner)? {
+            Cow::Borrowed(v) => visitor.visit_borrowed_str(v),
+            Cow::Owned(v) => visitor.visit_string(v),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        if is_typed(&self.inner) {
+            self.inner.deserialize_option(LenientVisitor(visitor))
+        } else {
+            self.inner.deserialize_any(LenientOptionVisitor(visitor))
+        }
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.inner.deserialize_ignored_any(visitor)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+/// Defines `visit_*` methods which pass the value on to the inner visitor.
+macro_rules! visit_forward {
+    ($($method:ident($ty:ty);)*) => {
+        $(
+            fn $method<E>(self, v: $ty) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                self.0.$method(v)
+            }
+        )*
+    };
+}
+
+/// A visitor which passes each value on to the inner visitor, and wraps any
+/// nested values in a [`LenientDeserializer`].
+struct LenientVisitor<V>(V);
+
+impl<'de, V> Visitor<'de> for LenientVisitor<V>
+where
+    V: Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.expecting(formatter)
+    }
+
+    visit_forward! {
+        visit_bool(bool);
+        visit_i8(i8);
+        visit_i16(i16);
+        visit_i32(i32);
+        visit_i64(i64);
+        visit_i128(i128);
+        visit_u8(u8);
+        visit_u16(u16);
+        visit_u32(u32);
+        visit_u64(u64);
+        visit_u128(u128);
+        visit_f32(f32);
+        visit_f64(f64);
+        visit_char(char);
+        visit_str(&str);
+        visit_borrowed_str(&'de str);
+        visit_string(String);
+        visit_bytes(&[u8]);
+        visit_borrowed_bytes(&'de [u8]);
+        visit_byte_buf(Vec<u8>);
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_none()
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.0.visit_some(LenientDeserializer::new(deserializer))
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_unit()
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.0
+            .visit_newtype_struct(LenientDeserializer::new(deserializer))
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        self.0.visit_seq(LenientAccess(seq))
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        self.0.visit_map(LenientAccess(map))
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: EnumAccess<'de>,
+    {
+        self.0.visit_enum(LenientAccess(data))
+    }
+}
+
+/// A visitor for an `Option`, which calls `visit_none` on the inner visitor
+/// for a `null` value or an empty string, and otherwise `visit_some` with a
+/// [`LenientDeserializer`] for the value.
+struct LenientOptionVisitor<V>(V);
+
+impl<'de, V> LenientOptionVisitor<V>
+where
+    V: Visitor<'de>,
+{
+    #[inline]
+    fn some<D>(self, deserializer: D) -> Result<V::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.0.visit_some(LenientDeserializer::new(deserializer))
+    }
+}
+
+impl<'de, V> Visitor<'de> for LenientOptionVisitor<V>
+where
+    V: Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.expecting(formatter)
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.some(v.into_deserializer())
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.some(v.into_deserializer())
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.some(v.into_deserializer())
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.some(v.into_deserializer())
+    }
+
+    fn visit_char<E>(self, v: char) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.some(v.into_deserializer())
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if v.is_empty() {
+            self.0.visit_none()
+        } else {
+            self.some(v.into_deserializer())
+        }
+    }
+
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if v.is_empty() {
+            self.0.visit_none()
+        } else {
+            self.some(BorrowedStrDeserializer::new(v))
+        }
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if v.is_empty() {
+            self.0.visit_none()
+        } else {
+            self.some(v.into_deserializer())
+        }
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.some(BytesDeserializer::new(v))
+    }
+
+    fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.some(BorrowedBytesDeserializer::new(v))
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_none()
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.some(deserializer)
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_none()
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        self.some(SeqAccessDeserializer::new(seq))
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        self.some(MapAccessDeserializer::new(map))
+    }
+}
+
+/// Wraps each element of a sequence or entry of a map, or the variant of an
+/// enum, in a [`LenientDeserializer`].
+struct LenientAccess<A>(A);
+
+impl<'de, A> SeqAccess<'de> for LenientAccess<A>
+where
+    A: SeqAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_element_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Self::Error>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        self.0.next_element_seed(LenientSeed(seed))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.0.size_hint()
+    }
+}
+
+impl<'de, A> MapAccess<'de> for LenientAccess<A>
+where
+    A: MapAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        self.0.next_key_seed(LenientSeed(seed))
+    }
+
+    fn next_value_seed<S>(&mut self, seed: S) -> Result<S::Value, Self::Error>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        self.0.next_value_seed(LenientSeed(seed))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.0.size_hint()
+    }
+}
+
+impl<'de, A> EnumAccess<'de> for LenientAccess<A>
+where
+    A: EnumAccess<'de>,
+{
+    type Error = A::Error;
+    type Variant = LenientAccess<A::Variant>;
+
+    fn variant_seed<S>(self, seed: S) -> Result<(S::Value, Self::Variant), Self::Error>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        self.0
+            .variant_seed(LenientSeed(seed))
+            .map(|(value, variant)| (value, LenientAccess(variant)))
+    }
+}
+
+impl<'de, A> VariantAccess<'de> for LenientAccess<A>
+where
+    A: VariantAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        self.0.unit_variant()
+    }
+
+    fn newtype_variant_seed<S>(self, seed: S) -> Result<S::Value, Self::Error>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        self.0.newtype_variant_seed(LenientSeed(seed))
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.0.tuple_variant(len, LenientVisitor(visitor))
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.0.struct_variant(fields, LenientVisitor(visitor))
+    }
+}
+
+/// A seed which de-serializes its value with a [`LenientDeserializer`].
+struct LenientSeed<S>(S);
+
+impl<'de, S> DeserializeSeed<'de> for LenientSeed<S>
+where
+    S: DeserializeSeed<'de>,
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.0.deserialize(LenientDeserializer::new(deserializer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::collections::{BTreeMap, HashMap};
+
+    fn from_str<'a, T: Deserialize<'a>>(json: &'a str) -> Result<T, serde_json::Error> {
+        let mut de = serde_json::Deserializer::from_str(json);
+        T::deserialize(LenientDeserializer::new(&mut de))
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Record {
+        id: u32,
+        offset: i16,
+        active: bool,
+        grade: f32,
+        initial: char,
+        name: String,
+        limit: Option<u64>,
+        ratio: Option<f64>,
+        tags: Vec<String>,
+        counts: BTreeMap<String, u8>,
+        kind: Kind,
+        shape: Shape,
+        inner: Option<Inner>,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Inner {
+        enabled: bool,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    enum Kind {
+        Small,
+        Large,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    enum Shape {
+        Circle(f64),
+        Rect { w: u64, h: u64 },
+    }
+
+    #[test]
+    fn test_lenient_document() {
+        let json = r#"{
+            "id": "7", "offset": -3.0, "active": "yes", "grade": "81.5",
+            "initial": " J ", "name": 42, "limit": "", "ratio": "0.5",
+            "tags": ["a", 1, true], "counts": {"x": "1", "y": 2.0},
+            "kind": "Large", "shape": {"Rect": {"w": "2", "h": 3}},
+            "inner": {"enabled": "on"}
+        }"#;
+
+        assert_eq!(
+            from_str::<Record>(json).unwrap(),
+            Record {
+                id: 7,
+                offset: -3,
+                active: true,
+                grade: 81.5,
+                initial: 'J',
+                name: "42".into(),
+                limit: None,
+                ratio: Some(0.5),
+                tags: vec!["a".into(), "1".into(), "true".into()],
+                counts: BTreeMap::from([("x".into(), 1), ("y".into(), 2)]),
+                kind: Kind::Large,
+                shape: Shape::Rect { w: 2, h: 3 },
+                inner: Some(Inner { enabled: true }),
+            }
+        );
+    }
+
+    #[test]
+    fn test_lenient_nested_values() {
+        let shape: Shape = from_str(r#"{"Circle": "1.5"}"#).unwrap();
+        assert_eq!(shape, Shape::Circle(1.5));
+
+        let values: Vec<Option<u64>> = from_str(r#"["1", null, "", 2.0]"#).unwrap();
+        assert_eq!(values, [Some(1), None, None, Some(2)]);
+
+        let map: HashMap<u64, bool> = from_str(r#"{"1": "yes", "2": 0}"#).unwrap();
+        assert_eq!(map, HashMap::from([(1, true), (2, false)]));
+
+        let tuple: (u8, String) = from_str(r#"["255", 1.5]"#).unwrap();
+        assert_eq!(tuple, (255, "1.5".into()));
+    }
+
+    #[test]
+    fn test_lenient_errors() {
+        // An invalid value is an error, even for an `Option`.
+        assert!(from_str::<u64>(r#""x""#).is_err());
+        assert!(from_str::<Option<u64>>(r#""x""#).is_err());
+        assert!(from_str::<u8>("256").is_err());
+        assert!(from_str::<Kind>(r#""Medium""#).is_err());
+    }
+
+    #[cfg(feature = "typed")]
+    #[test]
+    fn test_lenient_binary_format() {
+        use bincode::Options;
+
+        // With the `typed` feature, the concrete type is requested from a
+        // format which is not human-readable, so the adapter can be used
+        // with it as well.
+        let bytes = bincode::serialize(&(3_u64, Some(true), "x")).unwrap();
+        let options = bincode::DefaultOptions::new().with_fixint_encoding();
+        let mut de = bincode::Deserializer::from_slice(&bytes, options);
+
+        let value = <(u64, Option<bool>, String)>::deserialize(LenientDeserializer::new(&mut de));
+        assert_eq!(value.unwrap(), (3, Some(true), "x".into()));
+    }
+}
@@ -0,0 +1,504 @@
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use core::fmt;
+#[cfg(any(feature = "std", feature = "indexmap"))]
+use core::hash::{BuildHasher, Hash};
+use core::marker::PhantomData;
+#[cfg(feature = "indexmap")]
+use indexmap::IndexMap;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+use crate::de::{self, Deserialize, DeserializeSeed, Deserializer, MapAccess, Visitor};
+use crate::de_coerce::Coerce;
+use crate::lenient::{Lenient, LenientOpt};
+
+/// A map type which [`as_map_keys`] can de-serialize into.
+///
+/// This is implemented for [`BTreeMap`], for `HashMap` when the `std`
+/// feature is enabled, and for `IndexMap` (which preserves the order of the
+/// entries) when the `indexmap` feature is enabled.
+pub trait InsertMap: Sized {
+    /// The type of the keys.
+    type Key;
+    /// The type of the values.
+    type Value;
+
+    /// Returns an empty map, with room for (about) `capacity` entries.
+    fn with_capacity(capacity: usize) -> Self;
+
+    /// Returns `true` if the map contains the `key`.
+    fn contains_key(&self, key: &Self::Key) -> bool;
+
+    /// Inserts the `key` and `value` into the map.
+    fn insert(&mut self, key: Self::Key, value: Self::Value);
+}
+
+impl<K: Ord, V> InsertMap for BTreeMap<K, V> {
+    type Key = K;
+    type Value = V;
+
+    fn with_capacity(_capacity: usize) -> Self {
+        BTreeMap::new()
+    }
+
+    fn contains_key(&self, key: &K) -> bool {
+        BTreeMap::contains_key(self, key)
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        BTreeMap::insert(self, key, value);
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K, V, S> InsertMap for HashMap<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher + Default,
+{
+    type Key = K;
+    type Value = V;
+
+    fn with_capacity(capacity: usize) -> Self {
+        HashMap::with_capacity_and_hasher(capacity, S::default())
+    }
+
+    fn contains_key(&self, key: &K) -> bool {
+        HashMap::contains_key(self, key)
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        HashMap::insert(self, key, value);
+    }
+}
+
+#[cfg(feature = "indexmap")]
+impl<K, V, S> InsertMap for IndexMap<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher + Default,
+{
+    type Key = K;
+    type Value = V;
+
+    fn with_capacity(capacity: usize) -> Self {
+        IndexMap::with_capacity_and_hasher(capacity, S::default())
+    }
+
+    fn contains_key(&self, key: &K) -> bool {
+        IndexMap::contains_key(self, key)
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        IndexMap::insert(self, key, value);
+    }
+}
+
+/// De-serialize a map, such as a `HashMap<u64, V>`, whose keys are coerced
+/// with the same rules as the crate's helper functions (for example,
+/// [`as_u64`](crate::as_u64) for a `u64` key). The values are de-serialized
+/// with their own `Deserialize` implementation.
+///
+/// Since the keys of a JSON object are always strings, this allows keys
+/// such as `"1.0"` or `" 7"` (surrounding whitespace is ignored, except for
+/// `String` keys) to be used for a map with numeric or `bool` keys:
+///
+/// ```rust
+/// use std::collections::BTreeMap;
+///
+/// use serde::Deserialize;
+/// use serde_this_or_that::as_map_keys;
+///
+/// #[derive(Deserialize)]
+/// struct Msg {
+///     #[serde(deserialize_with = "as_map_keys")]
+///     accounts: BTreeMap<u64, String>,
+/// }
+///
+/// let m: Msg = serde_json::from_str(r#"{"accounts": {"1.0": "a", " 7": "b"}}"#).unwrap();
+/// assert_eq!(m.accounts[&1], "a");
+/// assert_eq!(m.accounts[&7], "b");
+/// ```
+///
+/// The key type needs to implement [`Coerce`](crate::Coerce). To coerce
+/// the keys of a map which is nested in another type, use
+/// [`Lenient`](crate::Lenient) keys instead, such as a
+/// `Vec<HashMap<Lenient<u64>, V>>`; note that duplicate keys are not
+/// detected in that case.
+///
+/// # Errors
+/// Returns an error if a key cannot be coerced, if a value cannot be
+/// de-serialized, or if two keys are the same after they are coerced, such
+/// as `"1"` and `"1.0"`.
+///
+pub fn as_map_keys<'de, D, M>(deserializer: D) -> Result<M, D::Error>
+where
+    D: Deserializer<'de>,
+    M: InsertMap,
+    M::Key: Coerce + fmt::Debug,
+    M::Value: Deserialize<'de>,
+{
+    deserializer.deserialize_map(DeserializeMapKeysWithVisitor(PhantomData::<M>))
+}
+
+struct DeserializeMapKeysWithVisitor<M>(PhantomData<M>);
+
+impl<'de, M> Visitor<'de> for DeserializeMapKeysWithVisitor<M>
+where
+    M: InsertMap,
+    M::Key: Coerce + fmt::Debug,
+    M::Value: Deserialize<'de>,
+{
+    type Value = M;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a map")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut out = M::with_capacity(map.size_hint().unwrap_or(0));
+
+        while let Some(key) = map.next_key_seed(KeySeed(PhantomData::<M::Key>))? {
+            if out.contains_key(&key) {
+                return Err(de::Error::custom(format_args!(
+                    "duplicate key: Key `{key:?}` appears more than once after coercion"
+                )));
+            }
+            let value = map.next_value()?;
+            out.insert(key, value);
+        }
+
+        Ok(out)
+    }
+}
+
+/// De-serialize a map, such as a `HashMap<String, f64>`, whose values are
+/// coerced with the same rules as the crate's helper functions (for example,
+/// [`as_f64`](crate::as_f64) for an `f64` value). The keys are de-serialized
+/// with their own `Deserialize` implementation.
+///
+/// ```rust
+/// use std::collections::BTreeMap;
+///
+/// use serde::Deserialize;
+/// use serde_this_or_that::{as_map_of, as_map_of_some};
+///
+/// #[derive(Deserialize)]
+/// struct Metrics {
+///     #[serde(deserialize_with = "as_map_of")]
+///     usage: BTreeMap<String, f64>,
+///     #[serde(deserialize_with = "as_map_of_some")]
+///     limits: BTreeMap<String, u64>,
+/// }
+///
+/// let json = r#"{
+///     "usage": {"cpu": "12.5", "mem": 40, "disk": null},
+///     "limits": {"cpu": "4", "mem": "N/A"}
+/// }"#;
+/// let m: Metrics = serde_json::from_str(json).unwrap();
+///
+/// assert_eq!(m.usage["cpu"], 12.5);
+/// assert_eq!(m.usage["disk"], 0.0);
+/// assert_eq!(m.limits, BTreeMap::from([(String::from("cpu"), 4)]));
+/// ```
+///
+/// The value type needs to implement [`Coerce`](crate::Coerce); see also
+/// [`as_map_of_opt`] and [`as_map_of_some`] for optional values. The map can
+/// be a `HashMap`, a [`BTreeMap`], or (with the `indexmap` feature) an
+/// `IndexMap`, which preserves the order of the entries.
+///
+/// # Errors
+/// Returns an error if a key cannot be de-serialized, or if a value cannot
+/// be coerced.
+///
+pub fn as_map_of<'de, D, M>(deserializer: D) -> Result<M, D::Error>
+where
+    D: Deserializer<'de>,
+    M: InsertMap,
+    M::Key: Deserialize<'de>,
+    M::Value: Coerce,
+{
+    deserializer.deserialize_map(DeserializeMapOfWithVisitor::<M, Lenient<M::Value>>(
+        |Lenient(v)| Some(v),
+    ))
+}
+
+/// Same as [`as_map_of`], but coerces each value with the same rules as the
+/// crate's `as_opt` helper functions, such as
+/// [`as_opt_f64`](crate::as_opt_f64) for a `HashMap<String, Option<f64>>`.
+///
+/// A `null` value, or one of an *invalid* data type, is kept in the map as
+/// [`None`]; see [`as_map_of_some`] to leave out such entries instead.
+///
+/// # Errors
+/// Returns an error if a key cannot be de-serialized.
+///
+pub fn as_map_of_opt<'de, D, M, T>(deserializer: D) -> Result<M, D::Error>
+where
+    D: Deserializer<'de>,
+    M: InsertMap<Value = Option<T>>,
+    M::Key: Deserialize<'de>,
+    T: Coerce,
+{
+    deserializer.deserialize_map(DeserializeMapOfWithVisitor::<M, LenientOpt<T>>(
+        |LenientOpt(v)| Some(v),
+    ))
+}
+
+/// Same as [`as_map_of_opt`], but leaves out each entry whose value is
+/// [`None`], so that the map has a value of type `T` (rather than
+/// `Option<T>`) for each key.
+///
+/// # Errors
+/// Returns an error if a key cannot be de-serialized.
+///
+pub fn as_map_of_some<'de, D, M>(deserializer: D) -> Result<M, D::Error>
+where
+    D: Deserializer<'de>,
+    M: InsertMap,
+    M::Key: Deserialize<'de>,
+    M::Value: Coerce,
+{
+    deserializer.deserialize_map(DeserializeMapOfWithVisitor::<M, LenientOpt<M::Value>>(
+        LenientOpt::into_inner,
+    ))
+}
+
+/// Defines a shorthand for [`as_map_of`], with a fixed value type.
+macro_rules! as_map_of_fns {
+    ($($name:ident -> $ty:ty, $helper:literal;)*) => {
+        $(
+            #[doc = concat!(
+                "Same as [`as_map_of`], for a map with `",
+                stringify!($ty),
+                "` values, which are coerced with [`",
+                $helper,
+                "`](crate::",
+                $helper,
+                ")."
+            )]
+            pub fn $name<'de, D, M>(deserializer: D) -> Result<M, D::Error>
+            where
+                D: Deserializer<'de>,
+                M: InsertMap<Value = $ty>,
+                M::Key: Deserialize<'de>,
+            {
+                as_map_of(deserializer)
+            }
+        )*
+    };
+}
+
+as_map_of_fns! {
+    as_map_of_bool -> bool, "as_bool";
+    as_map_of_f64 -> f64, "as_f64";
+    as_map_of_i64 -> i64, "as_i64";
+    as_map_of_string -> String, "as_string";
+    as_map_of_u64 -> u64, "as_u64";
+}
+
+/// Inserts each entry of a map, whose value is de-serialized as a `W`
+/// (such as a [`Lenient`] value), if the function returns [`Some`] for it.
+struct DeserializeMapOfWithVisitor<M: InsertMap, W>(fn(W) -> Option<M::Value>);
+
+impl<'de, M, W> Visitor<'de> for DeserializeMapOfWithVisitor<M, W>
+where
+    M: InsertMap,
+    M::Key: Deserialize<'de>,
+    W: Deserialize<'de>,
+{
+    type Value = M;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a map")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut out = M::with_capacity(map.size_hint().unwrap_or(0));
+
+        while let Some(key) = map.next_key()? {
+            if let Some(value) = (self.0)(map.next_value()?) {
+                out.insert(key, value);
+            }
+        }
+
+        Ok(out)
+    }
+}
+
+/// De-serializes a map key with [`Coerce::coerce_key`].
+struct KeySeed<K>(PhantomData<K>);
+
+impl<'de, K: Coerce> DeserializeSeed<'de> for KeySeed<K> {
+    type Value = K;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        K::coerce_key(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Lenient;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct TestMapKeysStruct {
+        #[serde(deserialize_with = "as_map_keys")]
+        ids: BTreeMap<u64, String>,
+        #[serde(deserialize_with = "as_map_keys")]
+        offsets: BTreeMap<i64, f64>,
+        #[serde(default, deserialize_with = "as_map_keys")]
+        flags: BTreeMap<bool, u8>,
+    }
+
+    #[test]
+    fn test_as_map_keys() {
+        let json = r#"{
+            "ids": {"1": "a", "2.0": "b", " 7": "c"},
+            "offsets": {"-1": 0.5, "3": 1},
+            "flags": {"yes": 1, "False": 0}
+        }"#;
+        let deserialized: TestMapKeysStruct = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            deserialized.ids,
+            BTreeMap::from([
+                (1, String::from("a")),
+                (2, String::from("b")),
+                (7, String::from("c"))
+            ])
+        );
+        assert_eq!(deserialized.offsets, BTreeMap::from([(-1, 0.5), (3, 1.0)]));
+        assert_eq!(deserialized.flags, BTreeMap::from([(true, 1), (false, 0)]));
+    }
+
+    #[test]
+    fn test_as_map_keys_with_padded_bool_key() {
+        let json = r#"{"ids": {}, "offsets": {}, "flags": {" true": 1, "off ": 0}}"#;
+        let deserialized: TestMapKeysStruct = serde_json::from_str(json).unwrap();
+        assert_eq!(deserialized.flags, BTreeMap::from([(true, 1), (false, 0)]));
+    }
+
+    #[test]
+    fn test_as_map_keys_with_invalid_bool_key() {
+        let json = r#"{"ids": {}, "offsets": {}, "flags": {"maybe": 1}}"#;
+        let err = serde_json::from_str::<TestMapKeysStruct>(json).unwrap_err();
+        assert!(err.to_string().contains("maybe"), "{err}");
+    }
+
+    #[test]
+    fn test_as_map_keys_keeps_string_keys() {
+        #[derive(Deserialize)]
+        struct TestStringKeysStruct {
+            #[serde(deserialize_with = "as_map_keys")]
+            names: BTreeMap<String, u64>,
+        }
+
+        let json = r#"{"names": {" a ": 1, "b": 2}}"#;
+        let deserialized: TestStringKeysStruct = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            deserialized.names,
+            BTreeMap::from([(String::from(" a "), 1), (String::from("b"), 2)])
+        );
+    }
+
+    #[test]
+    fn test_as_map_keys_with_duplicate_keys() {
+        let json = r#"{"ids": {"1": "a", "1.0": "b"}, "offsets": {}}"#;
+        let err = serde_json::from_str::<TestMapKeysStruct>(json).unwrap_err();
+        assert!(err.to_string().contains("duplicate key"), "{err}");
+    }
+
+    #[test]
+    fn test_as_map_keys_with_invalid_key() {
+        let json = r#"{"ids": {"x": "a"}, "offsets": {}}"#;
+        let deserialized = serde_json::from_str::<TestMapKeysStruct>(json);
+        assert!(deserialized.is_err());
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct TestMapOfStruct {
+        #[serde(deserialize_with = "as_map_of_f64")]
+        usage: BTreeMap<String, f64>,
+        #[serde(deserialize_with = "as_map_of_opt")]
+        limits: BTreeMap<String, Option<u64>>,
+        #[serde(deserialize_with = "as_map_of_some")]
+        flags: BTreeMap<String, bool>,
+    }
+
+    #[test]
+    fn test_as_map_of() {
+        let json = r#"{
+            "usage": {"cpu": "12.5", "mem": 40, "disk": null},
+            "limits": {"cpu": "4", "mem": "N/A", "disk": null},
+            "flags": {"a": "yes", "b": "maybe", "c": 0}
+        }"#;
+        let deserialized: TestMapOfStruct = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            deserialized,
+            TestMapOfStruct {
+                usage: BTreeMap::from([
+                    (String::from("cpu"), 12.5),
+                    (String::from("mem"), 40.0),
+                    (String::from("disk"), 0.0)
+                ]),
+                limits: BTreeMap::from([
+                    (String::from("cpu"), Some(4)),
+                    (String::from("mem"), None),
+                    (String::from("disk"), None)
+                ]),
+                flags: BTreeMap::from([(String::from("a"), true), (String::from("c"), false)]),
+            }
+        );
+    }
+
+    #[test]
+    fn test_as_map_of_with_invalid_value() {
+        let json = r#"{"usage": {"cpu": "high"}, "limits": {}, "flags": {}}"#;
+        let deserialized = serde_json::from_str::<TestMapOfStruct>(json);
+        assert!(deserialized.is_err());
+    }
+
+    #[cfg(feature = "indexmap")]
+    #[test]
+    fn test_as_map_of_preserves_order() {
+        #[derive(Deserialize)]
+        struct TestIndexMapStruct {
+            #[serde(deserialize_with = "as_map_of_u64")]
+            counts: IndexMap<String, u64, std::hash::RandomState>,
+            #[serde(deserialize_with = "as_map_keys")]
+            names: IndexMap<u64, String, std::hash::RandomState>,
+        }
+
+        let json = r#"{
+            "counts": {"z": "1", "a": 2, "m": "3"},
+            "names": {"3": "c", "1.0": "a", "2": "b"}
+        }"#;
+        let deserialized: TestIndexMapStruct = serde_json::from_str(json).unwrap();
+
+        assert!(deserialized.counts.keys().eq(["z", "a", "m"]));
+        assert!(deserialized.counts.values().eq(&[1, 2, 3]));
+        assert!(deserialized.names.keys().eq(&[3, 1, 2]));
+    }
+
+    #[test]
+    fn test_lenient_map_keys() {
+        let json = r#"{"1.0": "a", "2": "b"}"#;
+        let deserialized: BTreeMap<Lenient<u64>, String> = serde_json::from_str(json).unwrap();
+        assert_eq!(deserialized[&Lenient(1)], "a");
+        assert_eq!(deserialized[&Lenient(2)], "b");
+    }
+}
@@ -0,0 +1,380 @@
+use alloc::{string::String, vec, vec::Vec};
+use core::fmt;
+use core::marker::PhantomData;
+
+use crate::de::value::{
+    BorrowedBytesDeserializer, BorrowedStrDeserializer, BytesDeserializer, MapAccessDeserializer,
+};
+use crate::de::{
+    self, Deserialize, DeserializeSeed, Deserializer, IntoDeserializer, MapAccess, SeqAccess,
+};
+use crate::typed::is_typed;
+
+/// De-serialize either a `null`, a single value, or an array of values
+/// as a *vector* of `T`.
+///
+/// This handles payloads such as those produced by many XML-to-JSON
+/// converters, which emit a single object when an element has one child,
+/// and an array when it has several:
+///
+/// ```rust
+/// use serde::Deserialize;
+/// use serde_this_or_that::as_one_or_many;
+///
+/// #[derive(Deserialize)]
+/// struct Item {
+///     id: u64,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Order {
+///     #[serde(deserialize_with = "as_one_or_many")]
+///     items: Vec<Item>,
+/// }
+///
+/// let o: Order = serde_json::from_str(r#"{"items": {"id": 1}}"#).unwrap();
+/// assert_eq!(o.items.len(), 1);
+///
+/// let o: Order = serde_json::from_str(r#"{"items": [{"id": 1}, {"id": 2}]}"#).unwrap();
+/// assert_eq!(o.items.len(), 2);
+/// ```
+///
+/// # Errors
+/// Returns an error if the single value, or any element of the array,
+/// cannot be de-serialized as `T`.
+///
+/// # Returns
+/// A vector with the single `T` value, or with each `T` in the array.
+/// A `null` value results in an empty vector.
+///
+pub fn as_one_or_many<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    if is_typed(&deserializer) {
+        return Vec::<T>::deserialize(deserializer);
+    }
+
+    deserializer.deserialize_any(DeserializeOneOrManyWithVisitor(PhantomData::<T>))
+}
+
+/// Same as [`as_one_or_many`], but de-serializes the single value, or each
+/// element of the array, with the given `visitor`.
+///
+/// This lets the crate's coercing helpers be applied to each element, by
+/// using their visitors from the [`visitor`](crate::visitor) module:
+///
+/// ```rust
+/// use serde::{Deserialize, Deserializer};
+/// use serde_this_or_that::as_one_or_many_with;
+/// use serde_this_or_that::visitor::DeserializeU64WithVisitor;
+///
+/// fn as_many_u64<'de, D>(deserializer: D) -> Result<Vec<u64>, D::Error>
+/// where
+///     D: Deserializer<'de>,
+/// {
+///     as_one_or_many_with(deserializer, DeserializeU64WithVisitor)
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Msg {
+///     #[serde(deserialize_with = "as_many_u64")]
+///     ids: Vec<u64>,
+/// }
+///
+/// let m: Msg = serde_json::from_str(r#"{"ids": "3"}"#).unwrap();
+/// assert_eq!(m.ids, [3]);
+///
+/// let m: Msg = serde_json::from_str(r#"{"ids": [1, "2", 3.0]}"#).unwrap();
+/// assert_eq!(m.ids, [1, 2, 3]);
+/// ```
+///
+/// Note that a visitor can't tell the de-serializer which type it expects,
+/// so unlike [`as_one_or_many`], this always uses `deserialize_any`, even
+/// with the `typed` feature; as such, it does not support a format which is
+/// not self-describing, such as `bincode`.
+///
+pub fn as_one_or_many_with<'de, D, V>(
+    deserializer: D,
+    visitor: V,
+) -> Result<Vec<V::Value>, D::Error>
+where
+    D: Deserializer<'de>,
+    V: de::Visitor<'de> + Clone,
+{
+    deserializer.deserialize_any(DeserializeOneOrManyWithVisitor(VisitorSeed(visitor)))
+}
+
+/// A seed which de-serializes a value with a (cloned) visitor.
+#[derive(Clone)]
+struct VisitorSeed<V>(V);
+
+impl<'de, V> DeserializeSeed<'de> for VisitorSeed<V>
+where
+    V: de::Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self.0)
+    }
+}
+
+struct DeserializeOneOrManyWithVisitor<S>(S);
+
+impl<S> DeserializeOneOrManyWithVisitor<S> {
+    #[inline]
+    fn one<'de, D>(self, deserializer: D) -> Result<Vec<S::Value>, D::Error>
+    where
+        S: DeserializeSeed<'de>,
+        D: Deserializer<'de>,
+    {
+        self.0.deserialize(deserializer).map(|v| vec![v])
+    }
+}
+
+impl<'de, S> de::Visitor<'de> for DeserializeOneOrManyWithVisitor<S>
+where
+    S: DeserializeSeed<'de> + Clone,
+{
+    type Value = Vec<S::Value>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a single value or an array of values")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.one(v.into_deserializer())
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.one(v.into_deserializer())
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.one(v.into_deserializer())
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.one(v.into_deserializer())
+    }
+
+    fn visit_char<E>(self, v: char) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.one(v.into_deserializer())
+    }
+
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.one(BorrowedStrDeserializer::new(v))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.one(v.into_deserializer())
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.one(v.into_deserializer())
+    }
+
+    fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.one(BorrowedBytesDeserializer::new(v))
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.one(BytesDeserializer::new(v))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut out = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(v) = seq.next_element_seed(self.0.clone())? {
+            out.push(v);
+        }
+        Ok(out)
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        self.one(MapAccessDeserializer::new(map))
+    }
+
+    /// We encounter a `null` value; this default implementation returns an
+    /// "empty" vector.
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Vec::new())
+    }
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Vec::new())
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::visitor::{DeserializeBoolWithVisitor, DeserializeOptionalU64WithVisitor};
+    use serde::Deserialize;
+
+    mod as_one_or_many_tests {
+        use super::*;
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Child {
+            name: String,
+        }
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct TestOneOrManyStruct {
+            #[serde(deserialize_with = "as_one_or_many")]
+            field: Vec<Child>,
+        }
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct TestOneOrManyStrStruct<'a> {
+            #[serde(borrow, deserialize_with = "as_one_or_many")]
+            field: Vec<&'a str>,
+        }
+
+        #[test]
+        fn test_as_one_or_many_with_single_map() {
+            let json = r#"{"field": {"name": "a"}}"#;
+            let deserialized: TestOneOrManyStruct = serde_json::from_str(json).unwrap();
+            assert_eq!(
+                deserialized.field,
+                [Child {
+                    name: "a".to_owned()
+                }]
+            );
+        }
+
+        #[test]
+        fn test_as_one_or_many_with_array() {
+            let json = r#"{"field": [{"name": "a"}, {"name": "b"}]}"#;
+            let deserialized: TestOneOrManyStruct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized.field.len(), 2);
+            assert_eq!(deserialized.field[1].name, "b");
+        }
+
+        #[test]
+        fn test_as_one_or_many_with_null() {
+            let json = r#"{"field": null}"#;
+            let deserialized: TestOneOrManyStruct = serde_json::from_str(json).unwrap();
+            assert!(deserialized.field.is_empty());
+        }
+
+        #[test]
+        fn test_as_one_or_many_with_borrowed_strings() {
+            let json = r#"{"field": "a"}"#;
+            let deserialized: TestOneOrManyStrStruct<'_> = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized.field, ["a"]);
+
+            let json = r#"{"field": ["a", "b"]}"#;
+            let deserialized: TestOneOrManyStrStruct<'_> = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized.field, ["a", "b"]);
+        }
+
+        #[test]
+        fn test_as_one_or_many_with_invalid_element() {
+            let json = r#"{"field": [{"name": "a"}, 1]}"#;
+            let deserialized = serde_json::from_str::<TestOneOrManyStruct>(json);
+            assert!(deserialized.is_err());
+        }
+    }
+
+    mod as_one_or_many_with_tests {
+        use super::*;
+
+        fn as_many_bool<'de, D>(deserializer: D) -> Result<Vec<bool>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            as_one_or_many_with(deserializer, DeserializeBoolWithVisitor)
+        }
+
+        fn as_many_opt_u64<'de, D>(deserializer: D) -> Result<Vec<Option<u64>>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            as_one_or_many_with(deserializer, DeserializeOptionalU64WithVisitor)
+        }
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct TestOneOrManyWithStruct {
+            #[serde(deserialize_with = "as_many_bool")]
+            flags: Vec<bool>,
+            #[serde(deserialize_with = "as_many_opt_u64")]
+            counts: Vec<Option<u64>>,
+        }
+
+        #[test]
+        fn test_as_one_or_many_with_single_values() {
+            let json = r#"{"flags": "Yes", "counts": "12"}"#;
+            let deserialized: TestOneOrManyWithStruct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized.flags, [true]);
+            assert_eq!(deserialized.counts, [Some(12)]);
+        }
+
+        #[test]
+        fn test_as_one_or_many_with_arrays() {
+            let json = r#"{"flags": [1, "off", true], "counts": [1, "", null, "x", 2.5]}"#;
+            let deserialized: TestOneOrManyWithStruct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized.flags, [true, false, true]);
+            assert_eq!(deserialized.counts, [Some(1), None, None, None, Some(3)]);
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use serde_json::Value;
+
+/// Lenient accessors for an untyped [`serde_json::Value`].
+///
+/// Unlike [`Value::as_u64`], which returns [`None`] for a string such as
+/// `"3"`, these apply the same rules as the helper functions (for example,
+/// [`as_u64`](crate::as_u64) for [`lenient_u64`](Self::lenient_u64)), so
+/// typed and untyped code gives identical results.
+///
+/// ```rust
+/// use serde_json::json;
+/// use serde_this_or_that::LenientValueExt;
+///
+/// let v = json!({"count": "3", "active": "yes", "grade": "", "id": 7});
+///
+/// assert_eq!(v["count"].lenient_u64().unwrap(), 3);
+/// assert!(v["active"].lenient_bool().unwrap());
+/// assert_eq!(v["grade"].lenient_opt_f64().unwrap(), None);
+/// assert_eq!(v["id"].lenient_string().unwrap(), "7");
+///
+/// // A missing key is `null`.
+/// assert_eq!(v["missing"].lenient_i64().unwrap(), 0);
+/// ```
+pub trait LenientValueExt {
+    /// Returns the value as an *unsigned* integer, as [`as_u64`](crate::as_u64)
+    /// does.
+    ///
+    /// # Errors
+    /// Returns an error in the same cases as [`as_u64`](crate::as_u64).
+    fn lenient_u64(&self) -> Result<u64, serde_json::Error>;
+
+    /// Returns the value as an *unsigned* integer wrapped in [`Some`], as
+    /// [`as_opt_u64`](crate::as_opt_u64) does.
+    ///
+    /// # Errors
+    /// Returns an error in the same cases as [`as_opt_u64`](crate::as_opt_u64),
+    /// such as for an array or an object.
+    fn lenient_opt_u64(&self) -> Result<Option<u64>, serde_json::Error>;
+
+    /// Returns the value as a *signed* integer, as [`as_i64`](crate::as_i64)
+    /// does.
+    ///
+    /// # Errors
+    /// Returns an error in the same cases as [`as_i64`](crate::as_i64).
+    fn lenient_i64(&self) -> Result<i64, serde_json::Error>;
+
+    /// Returns the value as a *signed* integer wrapped in [`Some`], as
+    /// [`as_opt_i64`](crate::as_opt_i64) does.
+    ///
+    /// # Errors
+    /// Returns an error in the same cases as [`as_opt_i64`](crate::as_opt_i64),
+    /// such as for an array or an object.
+    fn lenient_opt_i64(&self) -> Result<Option<i64>, serde_json::Error>;
+
+    /// Returns the value as a *float*, as [`as_f64`](crate::as_f64) does.
+    ///
+    /// # Errors
+    /// Returns an error in the same cases as [`as_f64`](crate::as_f64).
+    fn lenient_f64(&self) -> Result<f64, serde_json::Error>;
+
+    /// Returns the value as a *float* wrapped in [`Some`], as
+    /// [`as_opt_f64`](crate::as_opt_f64) does.
+    ///
+    /// # Errors
+    /// Returns an error in the same cases as [`as_opt_f64`](crate::as_opt_f64),
+    /// such as for an array or an object.
+    fn lenient_opt_f64(&self) -> Result<Option<f64>, serde_json::Error>;
+
+    /// Returns the value as a *boolean*, as [`as_bool`](crate::as_bool) does.
+    ///
+    /// # Errors
+    /// Returns an error in the same cases as [`as_bool`](crate::as_bool).
+    fn lenient_bool(&self) -> Result<bool, serde_json::Error>;
+
+    /// Returns the value as a *boolean* wrapped in [`Some`], as
+    /// [`as_opt_bool`](crate::as_opt_bool) does.
+    ///
+    /// # Errors
+    /// Returns an error in the same cases as
+    /// [`as_opt_bool`](crate::as_opt_bool), such as for an array or an object.
+    fn lenient_opt_bool(&self) -> Result<Option<bool>, serde_json::Error>;
+
+    /// Returns the value as a *string*, as [`as_string`](crate::as_string)
+    /// does.
+    ///
+    /// # Errors
+    /// Returns an error in the same cases as [`as_string`](crate::as_string).
+    fn lenient_string(&self) -> Result<String, serde_json::Error>;
+
+    /// Returns the value as a *string* wrapped in [`Some`], as
+    /// [`as_opt_string`](crate::as_opt_string) does.
+    ///
+    /// # Errors
+    /// Returns an error in the same cases as
+    /// [`as_opt_string`](crate::as_opt_string), such as for an array or an
+    /// object.
+    fn lenient_opt_string(&self) -> Result<Option<String>, serde_json::Error>;
+}
+
+impl LenientValueExt for Value {
+    #[inline]
+    fn lenient_u64(&self) -> Result<u64, serde_json::Error> {
+        crate::as_u64(self)
+    }
+
+    #[inline]
+    fn lenient_opt_u64(&self) -> Result<Option<u64>, serde_json::Error> {
+        crate::as_opt_u64(self)
+    }
+
+    #[inline]
+    fn lenient_i64(&self) -> Result<i64, serde_json::Error> {
+        crate::as_i64(self)
+    }
+
+    #[inline]
+    fn lenient_opt_i64(&self) -> Result<Option<i64>, serde_json::Error> {
+        crate::as_opt_i64(self)
+    }
+
+    #[inline]
+    fn lenient_f64(&self) -> Result<f64, serde_json::Error> {
+        crate::as_f64(self)
+    }
+
+    #[inline]
+    fn lenient_opt_f64(&self) -> Result<Option<f64>, serde_json::Error> {
+        crate::as_opt_f64(self)
+    }
+
+    #[inline]
+    fn lenient_bool(&self) -> Result<bool, serde_json::Error> {
+        crate::as_bool(self)
+    }
+
+    #[inline]
+    fn lenient_opt_bool(&self) -> Result<Option<bool>, serde_json::Error> {
+        crate::as_opt_bool(self)
+    }
+
+    #[inline]
+    fn lenient_string(&self) -> Result<String, serde_json::Error> {
+        crate::as_string(self)
+    }
+
+    #[inline]
+    fn lenient_opt_string(&self) -> Result<Option<String>, serde_json::Error> {
+        crate::as_opt_string(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Typed {
+        #[serde(deserialize_with = "crate::as_u64")]
+        u: u64,
+        #[serde(deserialize_with = "crate::as_opt_i64")]
+        i: Option<i64>,
+        #[serde(deserialize_with = "crate::as_f64")]
+        f: f64,
+        #[serde(deserialize_with = "crate::as_bool")]
+        b: bool,
+        #[serde(deserialize_with = "crate::as_opt_string")]
+        s: Option<String>,
+    }
+
+    #[test]
+    fn test_matches_typed() {
+        let values = [
+            json!({"u": "3", "i": "-2.5", "f": "", "b": "YES", "s": 1.5}),
+            json!({"u": 2.5, "i": "A+", "f": 7, "b": 0, "s": null}),
+            json!({"u": null, "i": true, "f": "-0.25", "b": "maybe", "s": "x"}),
+        ];
+        for v in values {
+            let typed = Typed::deserialize(&v).unwrap();
+            let untyped = Typed {
+                u: v["u"].lenient_u64().unwrap(),
+                i: v["i"].lenient_opt_i64().unwrap(),
+                f: v["f"].lenient_f64().unwrap(),
+                b: v["b"].lenient_bool().unwrap(),
+                s: v["s"].lenient_opt_string().unwrap(),
+            };
+            assert_eq!(typed, untyped);
+        }
+    }
+
+    #[test]
+    fn test_lenient_values() {
+        let v = json!({"n": "-7", "f": "1e3", "b": "off", "s": true});
+
+        assert_eq!(v["n"].lenient_i64().unwrap(), -7);
+        assert_eq!(v["n"].lenient_opt_u64().unwrap(), Some(0));
+        assert_eq!(v["f"].lenient_opt_f64().unwrap(), Some(1000.0));
+        assert_eq!(v["b"].lenient_opt_bool().unwrap(), Some(false));
+        assert_eq!(v["s"].lenient_string().unwrap(), "true");
+        assert_eq!(v["missing"].lenient_opt_u64().unwrap(), None);
+    }
+
+    #[test]
+    fn test_errors() {
+        let v = json!({"n": "abc", "list": [1, 2]});
+
+        assert!(v["n"].lenient_u64().is_err());
+        assert_eq!(v["n"].lenient_opt_u64().unwrap(), None);
+        assert!(v["list"].lenient_opt_u64().is_err());
+        assert!(json!(-1).lenient_u64().is_err());
+        assert!(json!(2).lenient_bool().is_err());
+    }
+}
@@ -0,0 +1,504 @@
+//! Support for values which a format wraps in a map.
+//!
+//! With its `arbitrary_precision` feature enabled, `serde_json` hands a
+//! number to a visitor as a map with a single entry: a private key, and the
+//! digits of the number as a string. The helpers here recognise that map,
+//! and pass the number on to the visitor as a `u64`, `i64`, or `f64`, just
+//! as `serde_json` would have done without the feature.
+//!
+//! With the `xml` feature, the text content of an XML element, which
+//! `quick-xml` and `serde-xml-rs` hand over as a map with a `$text` or
+//! `$value` entry (alongside any `@` attributes), is unwrapped as well.
+
+use core::fmt;
+
+use crate::de::{
+    self, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Unexpected, Visitor,
+};
+
+/// The key of the map which `serde_json` uses for a number, when its
+/// `arbitrary_precision` feature is enabled.
+const TOKEN: &str = "$serde_json::private::Number";
+
+/// Visits a value which is wrapped in a map: a number from `serde_json` with
+/// its `arbitrary_precision` feature, or (with the `xml` feature) the text
+/// content of an XML element. Any other map is an error, as it would be
+/// without this method.
+///
+/// Used by the `visit_map` method of the crate's visitors, including those
+/// generated by [`define_coercion!`](crate::define_coercion).
+///
+/// # Errors
+/// Returns an error if the map does not wrap a value, or if the visitor
+/// does not accept the value.
+pub fn visit_wrapped_map<'de, A, V>(mut map: A, visitor: V) -> Result<V::Value, A::Error>
+where
+    A: MapAccess<'de>,
+    V: Visitor<'de>,
+{
+    match visit_wrapped(&mut map, visitor)? {
+        Ok(value) => Ok(value),
+        Err(visitor) => Err(de::Error::invalid_type(Unexpected::Map, &visitor)),
+    }
+}
+
+/// Visits the value wrapped in the map, if there is one. Otherwise, the
+/// entries of the map are skipped, and the visitor is handed back.
+// Only the attributes of an XML element are skipped over, to get to the
+// text content; without the `xml` feature, the first key always decides.
+#[cfg_attr(not(feature = "xml"), allow(clippy::never_loop))]
+pub(crate) fn visit_wrapped<'de, A, V>(
+    map: &mut A,
+    visitor: V,
+) -> Result<Result<V::Value, V>, A::Error>
+where
+    A: MapAccess<'de>,
+    V: Visitor<'de>,
+{
+    loop {
+        match map.next_key_seed(WrappedKey)? {
+            Some(Key::Number) => return map.next_value_seed(NumberSeed(visitor)).map(Ok),
+            #[cfg(feature = "xml")]
+            Some(Key::Text) => {
+                let value = map.next_value_seed(AnySeed(visitor))?;
+                skip_entries(map)?;
+                return Ok(Ok(value));
+            }
+            #[cfg(feature = "xml")]
+            Some(Key::Attribute) => {
+                map.next_value::<IgnoredAny>()?;
+            }
+            Some(Key::Other) => {
+                map.next_value::<IgnoredAny>()?;
+                skip_entries(map)?;
+                return Ok(Err(visitor));
+            }
+            // An XML element without any text content, such as `<a/>` or
+            // `<a id="1"></a>`, is treated like a `null` value.
+            #[cfg(feature = "xml")]
+            None => return visitor.visit_unit().map(Ok),
+            #[cfg(not(feature = "xml"))]
+            None => return Ok(Err(visitor)),
+        }
+    }
+}
+
+/// Skips the remaining entries of a map.
+fn skip_entries<'de, A>(map: &mut A) -> Result<(), A::Error>
+where
+    A: MapAccess<'de>,
+{
+    while map.next_entry::<IgnoredAny, IgnoredAny>()?.is_some() {}
+    Ok(())
+}
+
+/// Passes a value on to the visitor, as it is.
+#[cfg(feature = "xml")]
+struct AnySeed<V>(V);
+
+#[cfg(feature = "xml")]
+impl<'de, V> DeserializeSeed<'de> for AnySeed<V>
+where
+    V: Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self.0)
+    }
+}
+
+/// Reads the digits of a number, and passes the number on to the visitor.
+struct NumberSeed<V>(V);
+
+impl<'de, V> DeserializeSeed<'de> for NumberSeed<V>
+where
+    V: Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'de, V> Visitor<'de> for NumberSeed<V>
+where
+    V: Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.expecting(formatter)
+    }
+
+    /// An integer is passed on as a `u64` or `i64`, and any other number as
+    /// an `f64`. The digits are passed on as they are for an integer which
+    /// doesn't fit in 64 bits, or a float which an `f64` can't hold exactly
+    /// (such as `0.10000000000000000001`), so that they are not rounded.
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        if v.contains(['.', 'e', 'E']) {
+            match v.parse::<f64>() {
+                Ok(f) if f.is_finite() && round_trips(v, f) => self.0.visit_f64(f),
+                _ => self.0.visit_str(v),
+            }
+        } else if let Ok(n) = v.parse::<u64>() {
+            self.0.visit_u64(n)
+        } else if let Ok(n) = v.parse::<i64>() {
+            self.0.visit_i64(n)
+        } else {
+            self.0.visit_str(v)
+        }
+    }
+}
+
+/// Returns `true` if the number `v` is the same as `f`, its closest `f64`,
+/// when written with the fewest digits that read back as `f`. For example,
+/// `12.50` is the same as `12.5`, but `0.10000000000000000001` is not the
+/// same as `0.1`.
+fn round_trips(v: &str, f: f64) -> bool {
+    use core::fmt::Write;
+
+    let mut buf = Buf::default();
+    if write!(buf, "{f:e}").is_err() {
+        return false;
+    }
+    match (Decimal::parse(v), buf.as_str().and_then(Decimal::parse)) {
+        (Some(a), Some(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// A number in scientific notation, with at most as many significant digits
+/// as an `f64` can need; leading and trailing zeros are left out.
+#[derive(PartialEq)]
+struct Decimal {
+    negative: bool,
+    digits: [u8; 17],
+    len: usize,
+    exponent: i64,
+}
+
+impl Decimal {
+    /// Parses the digits of a number, such as `-12.50` or `1e3`. Returns
+    /// `None` if the number has more significant digits than an `f64` can
+    /// hold, or (for safety) is not a valid number.
+    fn parse(v: &str) -> Option<Self> {
+        let (negative, v) = match v.as_bytes().first() {
+            Some(b'-') => (true, &v[1..]),
+            Some(b'+') => (false, &v[1..]),
+            _ => (false, v),
+        };
+        let (mantissa, exponent) = match v.find(['e', 'E']) {
+            Some(i) => (&v[..i], v[i + 1..].parse::<i64>().ok()?),
+            None => (v, 0),
+        };
+        let (int, frac) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+
+        let mut out = Self {
+            negative,
+            digits: [0; 17],
+            len: 0,
+            exponent: 0,
+        };
+        // The number of zeros seen since the last non-zero digit.
+        let mut zeros = 0;
+        for (i, d) in int.bytes().chain(frac.bytes()).enumerate() {
+            if !d.is_ascii_digit() {
+                return None;
+            }
+            if d == b'0' {
+                zeros += 1;
+                continue;
+            }
+            if out.len == 0 {
+                let position = i64::try_from(i).ok()?;
+                let int_len = i64::try_from(int.len()).ok()?;
+                out.exponent = exponent.checked_add(int_len - 1 - position)?;
+            } else {
+                for _ in 0..zeros {
+                    out.push(b'0')?;
+                }
+            }
+            zeros = 0;
+            out.push(d)?;
+        }
+        Some(out)
+    }
+
+    fn push(&mut self, d: u8) -> Option<()> {
+        *self.digits.get_mut(self.len)? = d;
+        self.len += 1;
+        Some(())
+    }
+}
+
+/// A buffer for an `f64` which is written in scientific notation.
+#[derive(Default)]
+struct Buf {
+    bytes: [u8; 32],
+    len: usize,
+}
+
+impl Buf {
+    fn as_str(&self) -> Option<&str> {
+        core::str::from_utf8(&self.bytes[..self.len]).ok()
+    }
+}
+
+impl fmt::Write for Buf {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        self.bytes
+            .get_mut(self.len..end)
+            .ok_or(fmt::Error)?
+            .copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+/// The kind of a key in a map.
+enum Key {
+    /// The key of a number from `serde_json`.
+    Number,
+    /// The key of the text content of an XML element.
+    #[cfg(feature = "xml")]
+    Text,
+    /// The key of an attribute of an XML element.
+    #[cfg(feature = "xml")]
+    Attribute,
+    /// Any other key.
+    Other,
+}
+
+/// Reads the key of a map, and returns its [`Key`] kind.
+struct WrappedKey;
+
+impl<'de> DeserializeSeed<'de> for WrappedKey {
+    type Value = Key;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for WrappedKey {
+    type Value = Key;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("any key")
+    }
+
+    fn visit_bool<E>(self, _: bool) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Key::Other)
+    }
+
+    fn visit_i64<E>(self, _: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Key::Other)
+    }
+
+    fn visit_u64<E>(self, _: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Key::Other)
+    }
+
+    fn visit_f64<E>(self, _: f64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Key::Other)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(match v {
+            TOKEN => Key::Number,
+            #[cfg(feature = "xml")]
+            "$text" | "$value" => Key::Text,
+            #[cfg(feature = "xml")]
+            _ if v.starts_with('@') => Key::Attribute,
+            _ => Key::Other,
+        })
+    }
+
+    fn visit_bytes<E>(self, _: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Key::Other)
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Key::Other)
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        IgnoredAny.visit_seq(seq).map(|_| Key::Other)
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        IgnoredAny.visit_map(map).map(|_| Key::Other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::de::value::{Error, MapDeserializer};
+    use serde::Deserialize;
+
+    use crate::{as_f64, as_opt_u64, as_string, as_u64};
+
+    /// A map like the one `serde_json` writes for a number, when its
+    /// `arbitrary_precision` feature is enabled.
+    fn number(digits: &str) -> MapDeserializer<'_, std::iter::Once<(&str, &str)>, Error> {
+        MapDeserializer::new(std::iter::once((TOKEN, digits)))
+    }
+
+    #[test]
+    fn test_number_map() {
+        assert_eq!(as_u64(number("3")).unwrap(), 3);
+        assert_eq!(as_u64(number("2.5")).unwrap(), 3);
+        assert_eq!(as_f64(number("-1e3")).unwrap(), -1000.0);
+        assert_eq!(as_opt_u64(number("-7")).unwrap(), None);
+        assert_eq!(as_string(number("0.1")).unwrap(), "0.1");
+    }
+
+    #[test]
+    fn test_number_map_keeps_digits() {
+        // Too large for a `u64` or an `f64`, so the digits are passed on.
+        let digits = "340282366920938463463374607431768211455";
+        assert_eq!(as_string(number(digits)).unwrap(), digits);
+        assert_eq!(as_u64(number(digits)).unwrap(), u64::MAX);
+
+        let digits = "1e400";
+        assert_eq!(as_string(number(digits)).unwrap(), digits);
+    }
+
+    #[test]
+    fn test_number_map_keeps_decimal_digits() {
+        // An `f64` can't hold this exactly, so the digits are passed on.
+        let digits = "0.10000000000000000001";
+        assert_eq!(as_string(number(digits)).unwrap(), digits);
+        assert_eq!(as_f64(number(digits)).unwrap(), 0.1);
+        assert_eq!(as_u64(number("2.50000000000000000001")).unwrap(), 3);
+
+        // These are exact, so they are passed on as an `f64`.
+        assert_eq!(as_string(number("12.50")).unwrap(), "12.5");
+        assert_eq!(as_string(number("-1.5E3")).unwrap(), "-1500");
+        assert_eq!(
+            as_string(number("0.30000000000000004")).unwrap(),
+            "0.30000000000000004"
+        );
+        assert_eq!(as_string(number("0.0")).unwrap(), "0");
+    }
+
+    crate::define_coercion! {
+        /// De-serialize a `u128`.
+        fn as_u128 -> u128, DeserializeU128WithVisitor;
+        /// De-serialize a `u128`, or [`None`] if it is invalid.
+        fn as_opt_u128, DeserializeOptionalU128WithVisitor;
+
+        expecting = "a u128";
+
+        value {
+            u64(v) => Ok(v.into()),
+            str(v) => v.parse().map_err(|_| {
+                de::Error::invalid_value(Unexpected::Str(v), &DeserializeU128WithVisitor)
+            }),
+        }
+
+        option {
+            i64(_) => None,
+            u64(v) => Some(v.into()),
+            str(v) => v.parse().ok(),
+        }
+    }
+
+    #[test]
+    fn test_number_map_u128() {
+        let digits = "340282366920938463463374607431768211455";
+        assert_eq!(as_u128(number(digits)).unwrap(), u128::MAX);
+        assert_eq!(as_opt_u128(number("7")).unwrap(), Some(7));
+        assert_eq!(as_opt_u128(number("-7")).unwrap(), None);
+    }
+
+    #[test]
+    fn test_other_map_is_an_error() {
+        let map = MapDeserializer::<_, Error>::new(std::iter::once(("a", 1_u64)));
+        let err = as_u64(map).unwrap_err();
+        assert!(err.to_string().starts_with("invalid type: map"), "{err}");
+    }
+
+    #[cfg(feature = "xml")]
+    #[test]
+    fn test_xml_element() {
+        use crate::{as_bool, as_opt_bool};
+
+        let element = |entries: &'static [(&'static str, &'static str)]| {
+            MapDeserializer::<_, Error>::new(entries.iter().copied())
+        };
+
+        assert!(as_bool(element(&[("@id", "1"), ("$text", "yes")])).unwrap());
+        assert_eq!(
+            as_opt_bool(element(&[("$value", "no")])).unwrap(),
+            Some(false)
+        );
+        assert_eq!(as_u64(element(&[("$text", "7"), ("@id", "1")])).unwrap(), 7);
+
+        // An element without any text content is treated like `null`.
+        assert_eq!(as_u64(element(&[])).unwrap(), 0);
+        assert_eq!(as_opt_u64(element(&[("@id", "1")])).unwrap(), None);
+
+        let err = as_u64(element(&[("@id", "1"), ("child", "7")])).unwrap_err();
+        assert!(err.to_string().starts_with("invalid type: map"), "{err}");
+    }
+
+    #[test]
+    fn test_serde_json_number() {
+        // With `arbitrary_precision`, this is a map which holds the number;
+        // either way, the helpers see the same value.
+        let value: serde_json::Value = serde_json::from_str("12.50").unwrap();
+        assert_eq!(as_f64(&value).unwrap(), 12.5);
+
+        #[derive(Deserialize)]
+        struct S {
+            #[serde(deserialize_with = "as_u64")]
+            n: u64,
+        }
+        let s: S = serde_json::from_str(r#"{"n": 18446744073709551615}"#).unwrap();
+        assert_eq!(s.n, u64::MAX);
+    }
+}
@@ -0,0 +1,602 @@
+//! De-serializing a struct from environment variables.
+//!
+//! Environment variables are always strings, so a struct whose fields use
+//! the crate's helper functions, such as [`as_bool`](crate::as_bool) and
+//! [`as_u64`](crate::as_u64), can be loaded from the environment as well as
+//! from (say) a JSON file. Fields with a plain numeric or `bool` type are
+//! parsed from the string as usual.
+//!
+//! Each field is read from the variable with the given prefix, followed by
+//! the field name in upper case, so that `num_attempts` is read from
+//! `APP_NUM_ATTEMPTS` for a prefix of `APP_`. Any underscores, dashes, and
+//! case are ignored when matching a field name, so that a field renamed to
+//! `numAttempts` is also read from `APP_NUM_ATTEMPTS`.
+//!
+//! - The fields of a nested struct are separated by [`SEPARATOR`] (`__`), so
+//!   that the `host` of a `db` field is read from `APP_DB__HOST`.
+//! - A sequence, such as a `Vec<u64>`, is read from a comma-separated list.
+//! - An `Option` is [`None`] when the variable is not set, or is empty.
+//!
+//! ```rust
+//! use serde::Deserialize;
+//! use serde_this_or_that::{as_bool, env};
+//!
+//! #[derive(Deserialize)]
+//! struct Config {
+//!     #[serde(deserialize_with = "as_bool")]
+//!     debug: bool,
+//!     port: u16,
+//!     hosts: Vec<String>,
+//!     db: Db,
+//! }
+//!
+//! #[derive(Deserialize)]
+//! struct Db {
+//!     url: String,
+//!     pool_size: Option<u32>,
+//! }
+//!
+//! let vars = [
+//!     ("APP_DEBUG", "yes"),
+//!     ("APP_PORT", "8080"),
+//!     ("APP_HOSTS", "a.example.com, b.example.com"),
+//!     ("APP_DB__URL", "postgres://localhost"),
+//! ];
+//! let config: Config = env::from_vars("APP_", vars).unwrap();
+//!
+//! assert!(config.debug);
+//! assert_eq!(config.port, 8080);
+//! assert_eq!(config.hosts, ["a.example.com", "b.example.com"]);
+//! assert_eq!(config.db.pool_size, None);
+//! ```
+//!
+//! Errors report the name of the variable which could not be
+//! de-serialized, or which is missing; see [`Error::var`].
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::de::value::{MapDeserializer, SeqDeserializer};
+use crate::de::{self, DeserializeOwned, Deserializer, IntoDeserializer, Visitor};
+
+/// The separator between the names of a struct field and a field of a
+/// nested struct, as in `APP_DB__HOST`.
+pub const SEPARATOR: &str = "__";
+
+/// The separator between the items of a sequence, as in `a,b,c`.
+const LIST_SEPARATOR: char = ',';
+
+/// De-serialize a value of type `T` from the environment variables which
+/// start with `prefix`, such as `APP_`.
+///
+/// Variables whose name or value is not valid Unicode are ignored. See the
+/// [module docs](self) for how fields are matched with variables.
+///
+/// ```rust,no_run
+/// use serde::Deserialize;
+/// use serde_this_or_that::{as_u64, env};
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     #[serde(deserialize_with = "as_u64")]
+///     max_retries: u64,
+/// }
+///
+/// // Reads `APP_MAX_RETRIES`.
+/// let config: Config = env::from_env("APP_").unwrap();
+/// ```
+///
+/// # Errors
+/// Returns an error if a variable cannot be de-serialized, or if a
+/// variable for a required field is not set.
+///
+pub fn from_env<T>(prefix: &str) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    from_vars(
+        prefix,
+        std::env::vars_os()
+            .filter_map(|(key, value)| Some((key.into_string().ok()?, value.into_string().ok()?))),
+    )
+}
+
+/// Same as [`from_env`], but reads the variables from `vars` instead of the
+/// environment of the current process.
+///
+/// # Errors
+/// Returns an error if a variable cannot be de-serialized, or if a
+/// variable for a required field is not set.
+///
+pub fn from_vars<T, I, K, V>(prefix: &str, vars: I) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: Into<String>,
+{
+    let mut root = Node::new(prefix.to_owned(), prefix.to_owned());
+
+    for (key, value) in vars {
+        let key = key.as_ref();
+        let rest = match key.strip_prefix(prefix) {
+            Some(rest) if !rest.is_empty() => rest,
+            _ => continue,
+        };
+
+        let mut node = &mut root;
+        let mut end = prefix.len();
+        for segment in rest.split(SEPARATOR) {
+            end += segment.len();
+            node = node
+                .children
+                .entry(segment.to_ascii_lowercase())
+                .or_insert_with(|| {
+                    Node::new(key[..end].to_owned(), key[..end].to_owned() + SEPARATOR)
+                });
+            end += SEPARATOR.len();
+        }
+        node.value = Some(value.into());
+    }
+
+    T::deserialize(root)
+}
+
+/// An error which occurred while de-serializing a value from environment
+/// variables.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error {
+    var: Option<String>,
+    message: String,
+    missing_field: Option<&'static str>,
+}
+
+impl Error {
+    /// Returns the name of the variable which could not be de-serialized, or
+    /// which is missing, if known.
+    pub fn var(&self) -> Option<&str> {
+        self.var.as_deref()
+    }
+
+    /// Sets the name of the variable, unless it is already known.
+    fn at(mut self, var: &str) -> Self {
+        if self.var.is_none() {
+            self.var = Some(var.to_owned());
+        }
+        self
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.var {
+            Some(var) => write!(f, "`{var}`: {}", self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error {
+            var: None,
+            message: msg.to_string(),
+            missing_field: None,
+        }
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        Error {
+            var: None,
+            message: String::from("missing environment variable"),
+            missing_field: Some(field),
+        }
+    }
+}
+
+/// A variable, and the variables nested under it.
+struct Node {
+    /// The name of the variable, such as `APP_DB`.
+    name: String,
+    /// The prefix of the nested variables, such as `APP_DB__`.
+    prefix: String,
+    /// The value of the variable, if it is set.
+    value: Option<String>,
+    /// The nested variables, by their name in lower case.
+    children: BTreeMap<String, Node>,
+}
+
+impl Node {
+    fn new(name: String, prefix: String) -> Self {
+        Node {
+            name,
+            prefix,
+            value: None,
+            children: BTreeMap::new(),
+        }
+    }
+
+    /// Returns a node for one item of a sequence.
+    fn item(name: &str, value: &str) -> Self {
+        let mut node = Node::new(name.to_owned(), String::new());
+        node.value = Some(value.trim().to_owned());
+        node
+    }
+
+    fn is_empty(&self) -> bool {
+        self.children.is_empty() && matches!(self.value.as_deref(), None | Some(""))
+    }
+}
+
+/// Returns `true` if a field name and a (lower case) variable name are the
+/// same, ignoring any underscores, dashes, and case.
+fn same_name(field: &str, var: &str) -> bool {
+    let normalize = |s: &str| {
+        s.chars()
+            .filter(|c| *c != '_' && *c != '-')
+            .map(|c| c.to_ascii_lowercase())
+            .collect::<String>()
+    };
+    normalize(field) == normalize(var)
+}
+
+/// Returns the name of the variable for a field, such as `NUM_ATTEMPTS` for
+/// either `num_attempts` or `numAttempts`.
+fn var_name(field: &str) -> String {
+    let mut out = String::with_capacity(field.len() + 4);
+    let mut prev_lower = false;
+    for c in field.chars() {
+        if c == '-' {
+            out.push('_');
+        } else {
+            if c.is_ascii_uppercase() && prev_lower {
+                out.push('_');
+            }
+            out.push(c.to_ascii_uppercase());
+        }
+        prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+    }
+    out
+}
+
+/// Parses the value of the variable as a primitive type, falling back to
+/// [`Deserializer::deserialize_any`] if it is not set or does not parse.
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value, Error>
+            where
+                V: Visitor<'de>,
+            {
+                match self.value.as_deref().and_then(|v| v.trim().parse().ok()) {
+                    Some(v) => visitor.$visit::<Error>(v).map_err(|e| e.at(&self.name)),
+                    None => self.deserialize_any(visitor),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Node {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Some(value) if self.children.is_empty() => visitor
+                .visit_string::<Error>(value)
+                .map_err(|e| e.at(&self.name)),
+            _ => self.deserialize_map(visitor),
+        }
+    }
+
+    deserialize_parsed! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        if self.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let items: Vec<Node> = match self.value.as_deref().map(str::trim) {
+            None | Some("") => Vec::new(),
+            Some(value) => value
+                .split(LIST_SEPARATOR)
+                .map(|item| Node::item(&self.name, item))
+                .collect(),
+        };
+
+        let mut seq = SeqDeserializer::new(items.into_iter());
+        let value = visitor.visit_seq(&mut seq).map_err(|e| e.at(&self.name))?;
+        seq.end().map_err(|e| e.at(&self.name))?;
+        Ok(value)
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let mut map = MapDeserializer::new(self.children.into_iter());
+        let value = visitor.visit_map(&mut map).map_err(|e| e.at(&self.name))?;
+        map.end().map_err(|e| e.at(&self.name))?;
+        Ok(value)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let name = self.name;
+        let prefix = self.prefix;
+        let entries = self.children.into_iter().map(|(key, node)| {
+            match fields.iter().find(|field| same_name(field, &key)) {
+                Some(field) => ((*field).to_owned(), node),
+                None => (key, node),
+            }
+        });
+
+        let mut map = MapDeserializer::new(entries);
+        let value = visitor
+            .visit_map(&mut map)
+            .map_err(|e| match e.missing_field {
+                Some(field) => e.at(&(prefix.clone() + &var_name(field))),
+                None => e,
+            })?;
+        map.end().map_err(|e| e.at(&name))?;
+        Ok(value)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Some(value) if self.children.is_empty() => visitor
+                .visit_enum(value.trim().into_deserializer())
+                .map_err(|e: Error| e.at(&self.name)),
+            _ => self.deserialize_map(visitor),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        i128 u128 char str string bytes byte_buf unit unit_struct tuple_struct
+        identifier ignored_any
+    }
+}
+
+impl IntoDeserializer<'_, Error> for Node {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{as_bool, as_opt_u64, as_u64};
+    use serde::Deserialize;
+    use std::collections::HashMap;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct TestConfig {
+        #[serde(deserialize_with = "as_bool")]
+        is_active: bool,
+        #[serde(deserialize_with = "as_u64")]
+        num_attempts: u64,
+        #[serde(default, deserialize_with = "as_opt_u64")]
+        timeout: Option<u64>,
+        port: u16,
+        ratio: Option<f32>,
+        tags: Vec<String>,
+        #[serde(default)]
+        ids: Vec<u64>,
+        db: TestDbConfig,
+        level: TestLevel,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct TestDbConfig {
+        url: String,
+        #[serde(default)]
+        options: HashMap<String, String>,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum TestLevel {
+        Info,
+        Debug,
+    }
+
+    fn vars() -> Vec<(&'static str, &'static str)> {
+        vec![
+            ("APP_IS_ACTIVE", "Yes"),
+            ("APP_NUM_ATTEMPTS", "2.5"),
+            ("APP_TIMEOUT", "N/A"),
+            ("APP_PORT", " 8080 "),
+            ("APP_RATIO", ""),
+            ("APP_TAGS", "a, b,c"),
+            ("APP_DB__URL", "postgres://localhost"),
+            ("APP_DB__OPTIONS__SSL", "on"),
+            ("APP_LEVEL", "debug"),
+            ("OTHER_PORT", "1"),
+        ]
+    }
+
+    #[test]
+    fn test_from_vars() {
+        let config: TestConfig = from_vars("APP_", vars()).unwrap();
+
+        assert_eq!(
+            config,
+            TestConfig {
+                is_active: true,
+                num_attempts: 3,
+                timeout: None,
+                port: 8080,
+                ratio: None,
+                tags: vec!["a".into(), "b".into(), "c".into()],
+                ids: vec![],
+                db: TestDbConfig {
+                    url: "postgres://localhost".into(),
+                    options: HashMap::from([("ssl".into(), "on".into())]),
+                },
+                level: TestLevel::Debug,
+            }
+        );
+    }
+
+    #[test]
+    fn test_from_vars_with_list_of_numbers() {
+        let mut vars = vars();
+        vars.push(("APP_IDS", "1,2, 3"));
+        let config: TestConfig = from_vars("APP_", vars).unwrap();
+        assert_eq!(config.ids, [1, 2, 3]);
+
+        let mut vars = self::vars();
+        vars.push(("APP_IDS", "1,x"));
+        let err = from_vars::<TestConfig, _, _, _>("APP_", vars).unwrap_err();
+        assert_eq!(err.var(), Some("APP_IDS"));
+    }
+
+    #[test]
+    fn test_from_vars_reports_invalid_var() {
+        let mut vars = vars();
+        vars.retain(|(k, _)| *k != "APP_PORT");
+        vars.push(("APP_PORT", "http"));
+
+        let err = from_vars::<TestConfig, _, _, _>("APP_", vars).unwrap_err();
+        assert_eq!(err.var(), Some("APP_PORT"));
+        assert!(err.to_string().starts_with("`APP_PORT`: "), "{err}");
+    }
+
+    #[test]
+    fn test_from_vars_reports_missing_var() {
+        let mut vars = vars();
+        vars.retain(|(k, _)| *k != "APP_DB__URL");
+
+        let err = from_vars::<TestConfig, _, _, _>("APP_", vars).unwrap_err();
+        assert_eq!(err.var(), Some("APP_DB__URL"));
+        assert_eq!(
+            err.to_string(),
+            "`APP_DB__URL`: missing environment variable"
+        );
+
+        let mut vars = self::vars();
+        vars.retain(|(k, _)| *k != "APP_NUM_ATTEMPTS");
+
+        let err = from_vars::<TestConfig, _, _, _>("APP_", vars).unwrap_err();
+        assert_eq!(err.var(), Some("APP_NUM_ATTEMPTS"));
+    }
+
+    /// Reads the first entry of a map (or of a struct, if `STRUCT` is set),
+    /// and leaves the rest.
+    #[derive(Debug)]
+    struct First<const STRUCT: bool>;
+
+    impl<'de, const STRUCT: bool> Deserialize<'de> for First<STRUCT> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            if STRUCT {
+                deserializer.deserialize_struct("First", &["a", "b"], First)
+            } else {
+                deserializer.deserialize_map(First)
+            }
+        }
+    }
+
+    impl<'de, const STRUCT: bool> de::Visitor<'de> for First<STRUCT> {
+        type Value = Self;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("a map")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self, A::Error>
+        where
+            A: de::MapAccess<'de>,
+        {
+            map.next_entry::<String, String>()?;
+            Ok(self)
+        }
+    }
+
+    #[test]
+    fn test_from_vars_reports_trailing_entries() {
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct TestOuter<T> {
+            db: T,
+        }
+
+        let vars = [("APP_DB__A", "1"), ("APP_DB__B", "2")];
+
+        let err = from_vars::<TestOuter<First<false>>, _, _, _>("APP_", vars).unwrap_err();
+        assert_eq!(err.var(), Some("APP_DB"));
+
+        let err = from_vars::<TestOuter<First<true>>, _, _, _>("APP_", vars).unwrap_err();
+        assert_eq!(err.var(), Some("APP_DB"));
+    }
+
+    #[test]
+    fn test_var_name() {
+        assert_eq!(var_name("num_attempts"), "NUM_ATTEMPTS");
+        assert_eq!(var_name("numAttempts"), "NUM_ATTEMPTS");
+        assert_eq!(var_name("max-retries2"), "MAX_RETRIES2");
+    }
+}
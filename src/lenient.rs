@@ -0,0 +1,224 @@
+use core::ops::{Deref, DerefMut};
+
+use crate::de::{Deserialize, Deserializer};
+use crate::de_coerce::Coerce;
+use crate::ser::{Serialize, Serializer};
+
+/// A value which is de-serialized with the same rules as the crate's helper
+/// functions, such as [`as_u64`](crate::as_u64) for a `Lenient<u64>`.
+///
+/// Unlike the helper functions, which can only be applied to a field with
+/// `deserialize_with`, this can be used anywhere a type is expected, such as
+/// in a `Vec`, a `HashMap`, or a tuple:
+///
+/// ```rust
+/// use serde::Deserialize;
+/// use serde_this_or_that::Lenient;
+///
+/// #[derive(Deserialize)]
+/// struct Msg {
+///     ids: Vec<Lenient<u64>>,
+/// }
+///
+/// let m: Msg = serde_json::from_str(r#"{"ids": [1, "2", 3.0, ""]}"#).unwrap();
+/// assert_eq!(m.ids, [Lenient(1), Lenient(2), Lenient(3), Lenient(0)]);
+/// ```
+///
+/// The value is serialized as-is.
+///
+/// `Lenient<T>` is supported for any type which implements
+/// [`Coerce`](crate::Coerce), including the integer types smaller than 64
+/// bits and `f32`. An integer which does not fit in a smaller type, such as
+/// `"300"` for a `u8`, results in an error.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Lenient<T>(pub T);
+
+/// An optional value which is de-serialized with the same rules as the
+/// crate's `as_opt` helper functions, such as
+/// [`as_opt_u64`](crate::as_opt_u64) for a `LenientOpt<u64>`.
+///
+/// This is the counterpart of [`Lenient`]: a `null` value, or one of an
+/// *invalid* data type, results in [`None`]. Likewise, an integer which
+/// does not fit in a smaller type, such as `"300"` for a `u8`, also results
+/// in [`None`].
+///
+/// > Note: unlike a field of type `Option<T>`, a missing field of type
+/// > `LenientOpt<T>` needs to be annotated with `#[serde(default)]`.
+///
+/// The value is serialized as-is.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LenientOpt<T>(pub Option<T>);
+
+impl<T> Lenient<T> {
+    /// Returns the inner value.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> LenientOpt<T> {
+    /// Returns the inner value.
+    pub fn into_inner(self) -> Option<T> {
+        self.0
+    }
+}
+
+impl<T> From<T> for Lenient<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> From<Option<T>> for LenientOpt<T> {
+    fn from(value: Option<T>) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> Deref for Lenient<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Lenient<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> Deref for LenientOpt<T> {
+    type Target = Option<T>;
+
+    fn deref(&self) -> &Option<T> {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for LenientOpt<T> {
+    fn deref_mut(&mut self) -> &mut Option<T> {
+        &mut self.0
+    }
+}
+
+impl<T: Serialize> Serialize for Lenient<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<T: Serialize> Serialize for LenientOpt<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Lenient<T>
+where
+    T: Coerce,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::coerce(deserializer).map(Lenient)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for LenientOpt<T>
+where
+    T: Coerce,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::coerce_opt(deserializer).map(LenientOpt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::collections::HashMap;
+    use std::vec::Vec;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct TestLenientStruct {
+        ids: Vec<Lenient<u32>>,
+        flags: (Lenient<bool>, LenientOpt<bool>),
+        grades: HashMap<String, Lenient<f64>>,
+        #[serde(default)]
+        name: LenientOpt<String>,
+    }
+
+    #[test]
+    fn test_lenient_in_containers() {
+        let json = r#"{
+            "ids": [1, "2", 2.5, "", null],
+            "flags": ["yes", "maybe"],
+            "grades": {"a": "81.5", "b": 90}
+        }"#;
+        let deserialized: TestLenientStruct = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            deserialized.ids,
+            [Lenient(1), Lenient(2), Lenient(3), Lenient(0), Lenient(0)]
+        );
+        assert_eq!(deserialized.flags, (Lenient(true), LenientOpt(None)));
+        assert_eq!(*deserialized.grades["a"], 81.5);
+        assert_eq!(*deserialized.grades["b"], 90.0);
+        assert_eq!(deserialized.name, LenientOpt(None));
+    }
+
+    #[test]
+    fn test_lenient_with_overflow() {
+        let deserialized = serde_json::from_str::<Lenient<u8>>("300");
+        assert!(deserialized.is_err());
+
+        let deserialized = serde_json::from_str::<Lenient<i8>>(r#""-129""#);
+        assert!(deserialized.is_err());
+
+        let deserialized: LenientOpt<u8> = serde_json::from_str(r#""300""#).unwrap();
+        assert_eq!(deserialized, LenientOpt(None));
+
+        let deserialized: LenientOpt<i16> = serde_json::from_str(r#""-129""#).unwrap();
+        assert_eq!(deserialized, LenientOpt(Some(-129)));
+    }
+
+    #[test]
+    fn test_lenient_opt_values() {
+        let deserialized: Vec<LenientOpt<u64>> =
+            serde_json::from_str(r#"[1, "2", "", null, "x", true]"#).unwrap();
+        assert_eq!(
+            deserialized,
+            [
+                LenientOpt(Some(1)),
+                LenientOpt(Some(2)),
+                LenientOpt(None),
+                LenientOpt(None),
+                LenientOpt(None),
+                LenientOpt(None)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lenient_serializes_transparently() {
+        let value = (
+            Lenient(3_u64),
+            LenientOpt(Some(true)),
+            LenientOpt::<f32>(None),
+        );
+        assert_eq!(serde_json::to_string(&value).unwrap(), "[3,true,null]");
+    }
+}
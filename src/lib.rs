@@ -1,6 +1,7 @@
 #![doc(html_root_url = "https://docs.rs/serde-this-or-that/0.5.0")]
 #![warn(rust_2018_idioms, missing_docs)]
 #![deny(warnings, dead_code, unused_imports, unused_mut)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
 //! [![github]](https://github.com/rnag/serde-this-or-that)&ensp;[![crates-io]](https://crates.io/crates/serde-this-or-that)&ensp;[![docs-rs]](https://docs.rs/serde-this-or-that)
 //!
@@ -93,6 +94,121 @@
 //! On error, or when there is a `null` value, or one of an *invalid* data type, the
 //! `as_opt` helper functions return [`None`] instead.
 //!
+//! ## Third-Party Types
+//!
+//! For a type which can't be annotated, such as one from a generated SDK,
+//! the [`LenientDeserializer`] adapter wraps any deserializer, and coerces
+//! each value in the document with the same rules as the helper functions:
+//!
+//! ```rust
+//! use serde::Deserialize;
+//! use serde_this_or_that::LenientDeserializer;
+//!
+//! let mut de = serde_json::Deserializer::from_str(r#"["1", 2.0, "", null]"#);
+//! let v = Vec::<Option<u64>>::deserialize(LenientDeserializer::new(&mut de)).unwrap();
+//! assert_eq!(v, [Some(1), Some(2), None, None]);
+//! ```
+//!
+//! ## Serializing as Strings
+//!
+//! In the other direction, for a consumer which expects every value to be a
+//! string, the [`StringifySerializer`] adapter (or the [`stringify()`] helper,
+//! for a single field) writes every number and boolean as a string, such as
+//! `"3"` or `"true"`; the strings for booleans can be changed, for example
+//! to `"Y"` and `"N"`, and floats can be written in scientific notation
+//! (with a [`FloatFormat`]).
+//!
+//! ## Patches
+//!
+//! For a *patch*, such as the body of a JSON Merge Patch request, a missing
+//! field and a `null` value have different meanings. The [`Patch<T>`] enum,
+//! and the [`as_patch`] helper for a field of type `Option<Option<T>>`, tell
+//! these apart, and otherwise coerce the value as usual.
+//!
+//! ## Binary Formats
+//!
+//! The helper functions use `deserialize_any`, which formats that are not
+//! self-describing (such as `bincode` and `postcard`) do not support. With
+//! the `typed` feature, for a format which is not [human-readable], the
+//! helpers (and the [`Lenient`], [`Coerced`], and [`Patch`] types) instead
+//! request the concrete type, such as `deserialize_u64` for [`as_u64`], so
+//! that the same struct can also be written to (and read from) a binary
+//! format.
+//!
+//! Self-describing binary formats, such as MessagePack and CBOR, are not
+//! human-readable either, but they support `deserialize_any`; without the
+//! `typed` feature, they are coerced just like JSON.
+//!
+//! [human-readable]: serde::Deserializer::is_human_readable
+//!
+//! ## Arbitrary Precision
+//!
+//! The helpers also work with the `arbitrary_precision` feature of
+//! `serde_json`. A number which doesn't fit in a `u64`, `i64`, or `f64` (or
+//! which an `f64` can't hold exactly, such as `0.10000000000000000001`) is
+//! then passed on as a string of its digits, so that `as_string`, or a
+//! visitor from [`define_coercion!`] that parses a `u128`, sees it exactly.
+//!
+//! ## XML
+//!
+//! XML de-serializers, such as `quick-xml` and `serde-xml-rs`, hand over an
+//! element as a map, with its text content under a `$text` or `$value` key
+//! (and its attributes under keys which start with `@`). With the `xml`
+//! feature, the helpers unwrap the text content of such a map, and treat an
+//! empty element, such as `<count/>`, like a `null` value.
+//!
+//! Note that with this feature, an empty map in any format (such as `{}` in
+//! JSON) is also treated like a `null` value.
+//!
+//! ## Environment Variables
+//!
+//! With the (default) `std` feature, the [`env`](mod@env) module de-serializes a struct
+//! from environment variables, such as `APP_NUM_ATTEMPTS` for a
+//! `num_attempts` field, with the same helper functions used for JSON.
+//!
+//! ## Without Serde
+//!
+//! The [`coerce`] module has the rules behind the helper functions as plain
+//! functions, such as [`coerce::str_to_bool`] and [`coerce::str_to_u64`], for
+//! values which do not come through `serde`, like command-line arguments or
+//! HTTP headers.
+//!
+//! ## Untyped JSON Values
+//!
+//! With the `serde_json` feature, the [`LenientValueExt`] trait adds methods
+//! such as `lenient_u64` and `lenient_opt_bool` to a [`serde_json::Value`],
+//! which apply the same rules as the helper functions, so that `"3"` is read
+//! as `3` where [`Value::as_u64`](serde_json::Value::as_u64) returns `None`.
+//!
+//! ## JSON Schema
+//!
+//! With the `schemars` feature, the [`schema`] module has a function for each
+//! helper, which describes the input it accepts (such as an integer, a string
+//! containing a number, or `null` for [`as_u64`]), for use with
+//! `#[schemars(schema_with = "...")]`.
+//!
+//! ## Observability
+//!
+//! With the `observe` feature enabled, the helper functions report each value
+//! they produce, along with whether it was converted *losslessly* (`"3"` to `3`),
+//! *lossily* (`2.5` rounded to `3`), *defaulted* (`""` to `0`), or *dropped*
+//! (`"A+"` to `None`). See the `observe` module for details; with the `tracing`
+//! feature, these are also emitted as [`tracing`](https://docs.rs/tracing) events.
+//!
+//! ## `no_std` Support
+//!
+//! The crate is `no_std` compatible when its default `std` feature is disabled.
+//! The numeric, `bool`, and `char` helpers (such as [`as_u64`], [`as_i64`],
+//! [`as_f64`], [`as_bool`], and their `as_opt` counterparts) never allocate, and
+//! are always available.
+//!
+//! Helpers which need to allocate, such as `as_string` and `as_opt_string`, are
+//! available when the `alloc` feature is enabled:
+//!
+//! ```toml
+//! [dependencies]
+//! serde-this-or-that = { version = "0.5", default-features = false, features = ["alloc"] }
+//! ```
 //!
 //! ## Readme Docs
 //!
@@ -103,11 +219,132 @@
 //! [`README.md`]: https://github.com/rnag/serde-this-or-that
 //!
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+/// Evaluates to the value of `$value`, reporting it as an
+/// [`Event`](observe::Event) when the `observe` feature is enabled.
+///
+/// The second form binds the value to `$out`, so that the outcome can
+/// depend on it.
+#[cfg(feature = "observe")]
+macro_rules! observed {
+    ($helper:literal, $input:ident, $out:ident = $value:expr, $outcome:expr) => {{
+        let $out = $value;
+        #[allow(unused_imports)]
+        use $crate::observe::Outcome::*;
+        $crate::observe::emit($helper, $crate::observe::InputKind::$input, $outcome, &$out);
+        $out
+    }};
+    ($helper:literal, $input:ident, $outcome:expr, $value:expr) => {
+        observed!($helper, $input, out = $value, $outcome)
+    };
+}
+
+#[cfg(not(feature = "observe"))]
+macro_rules! observed {
+    ($helper:literal, $input:ident, $out:ident = $value:expr, $outcome:expr) => {
+        $value
+    };
+    ($helper:literal, $input:ident, $outcome:expr, $value:expr) => {
+        $value
+    };
+}
+
+mod macros;
+
+pub mod coerce;
+mod coerced;
+#[cfg(feature = "alloc")]
+mod de_bytes;
+mod de_coerce;
 mod de_impl;
-mod de_impl_opt;
+#[cfg(feature = "serde_json")]
+mod de_json;
+#[cfg(feature = "alloc")]
+mod de_lenient;
+#[cfg(feature = "alloc")]
+mod de_map;
+#[cfg(feature = "alloc")]
+mod de_one_or_many;
+#[cfg(feature = "serde_json")]
+mod de_value;
+mod de_wrapped;
+#[cfg(feature = "std")]
+pub mod env;
+mod lenient;
+#[cfg(feature = "observe")]
+pub mod observe;
+mod patch;
+#[cfg(feature = "schemars")]
+pub mod schema;
+mod ser_stringify;
+mod typed;
+
+pub use coerced::{Coerced, Repr};
+#[cfg(feature = "alloc")]
+pub use de_bytes::{
+    as_bytes, as_bytes_in_order, as_opt_bytes, as_opt_bytes_in_order, ByteEncoding,
+    DEFAULT_BYTE_ENCODINGS,
+};
+pub use de_coerce::{as_coerce, as_opt_coerce, Coerce};
+pub use de_impl::{
+    as_bool, as_char, as_char_with, as_f64, as_i64, as_opt_bool, as_opt_char, as_opt_char_with,
+    as_opt_f64, as_opt_i64, as_opt_u64, as_str_ref, as_u64, CharMode,
+};
+#[cfg(feature = "alloc")]
+pub use de_impl::{
+    as_cow_str, as_opt_raw_string, as_opt_string, as_opt_string_with, as_raw_string, as_string,
+    as_string_with, StringMode,
+};
+#[cfg(feature = "serde_json")]
+pub use de_json::{as_json_str, as_opt_json_str};
+#[cfg(feature = "alloc")]
+pub use de_lenient::LenientDeserializer;
+#[cfg(feature = "alloc")]
+pub use de_map::{
+    as_map_keys, as_map_of, as_map_of_bool, as_map_of_f64, as_map_of_i64, as_map_of_opt,
+    as_map_of_some, as_map_of_string, as_map_of_u64, InsertMap,
+};
+#[cfg(feature = "alloc")]
+pub use de_one_or_many::{as_one_or_many, as_one_or_many_with};
+#[cfg(feature = "serde_json")]
+pub use de_value::LenientValueExt;
+pub use lenient::{Lenient, LenientOpt};
+pub use patch::{as_patch, Patch};
+pub use ser_stringify::{stringify, FloatFormat, StringifySerializer};
+
+/// The [`Visitor`](de::Visitor) types behind each of the helper functions.
+///
+/// These can be used to apply the crate's coercion rules in places where a
+/// `deserialize_with` function does not fit, such as with
+/// [`as_one_or_many_with`].
+pub mod visitor {
+    pub use crate::de_coerce::{DeserializeNarrowWithVisitor, DeserializeOptionalWithVisitor};
+    pub use crate::de_impl::{
+        DeserializeBoolWithVisitor, DeserializeCharWithVisitor, DeserializeF64WithVisitor,
+        DeserializeI64WithVisitor, DeserializeOptionalBoolWithVisitor,
+        DeserializeOptionalCharWithVisitor, DeserializeOptionalF64WithVisitor,
+        DeserializeOptionalI64WithVisitor, DeserializeOptionalU64WithVisitor,
+        DeserializeStrRefWithVisitor, DeserializeU64WithVisitor,
+    };
+    #[cfg(feature = "alloc")]
+    pub use crate::de_impl::{
+        DeserializeCowStrWithVisitor, DeserializeOptionalRawStringWithVisitor,
+        DeserializeOptionalStringWithVisitor, DeserializeRawStringWithVisitor,
+        DeserializeStringWithVisitor,
+    };
+}
+
+/// Items used by the crate's exported macros; not part of the public API.
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::de_wrapped::visit_wrapped_map;
+    pub use crate::typed::is_typed;
+    #[cfg(feature = "alloc")]
+    pub use alloc::string::String;
+}
 
-pub use de_impl::{as_bool, as_f64, as_i64, as_string, as_u64};
-pub use de_impl_opt::{as_opt_bool, as_opt_f64, as_opt_i64, as_opt_string, as_opt_u64};
 #[doc(hidden)]
 pub use serde;
 #[doc(hidden)]
@@ -0,0 +1,290 @@
+/// Defines an `as_*` and `as_opt_*` pair of helper functions, along with the
+/// [`Visitor`](serde::de::Visitor) types behind them, from a specification of
+/// how each kind of input maps to the output type.
+///
+/// This is how the crate's own helper functions, such as [`as_u64`] and
+/// [`as_opt_u64`], are defined.
+///
+/// The specification starts with the signature (and docs) of each function
+/// and the name of its visitor, followed by the visitors' `expecting`
+/// message, and (optionally) a `hint`. Then, two blocks list the kinds of
+/// input which are accepted:
+///
+/// - `value`, where each entry evaluates to a `Result` with the output type,
+///   for the `as_*` function; any other kind of input results in an error.
+/// - `option`, where each entry evaluates to an `Option` of the output type,
+///   for the `as_opt_*` function; any other kind of input results in an
+///   error, so list each kind which should result in [`None`] instead.
+///
+/// Each entry is one of `bool(v)`, `i64(v)`, `u64(v)`, `f64(v)`, `char(v)`,
+/// `str(v)` (a `&str`), `string(v)` (an owned `String`, which needs the
+/// `alloc` feature), or `unit` (a `null` value), followed by `=>` and an
+/// expression. Errors can be created with the [`de::Error`](serde::de::Error)
+/// trait, and the visitor itself can be used as the "expected" value.
+///
+/// The functions use [`deserialize_any`](serde::Deserializer::deserialize_any),
+/// which formats that are not self-describing (such as `bincode`) do not
+/// support. With a `hint`, such as `hint = deserialize_u64;`, and the `typed`
+/// feature of this crate, the `as_*` function instead calls that method (and
+/// the `as_opt_*` function calls `deserialize_option`) when the format is not
+/// [human-readable](serde::Deserializer::is_human_readable).
+///
+/// Both visitors treat a `None` value like a `null` value (that is, like
+/// `unit`), and pass the value inside of a `Some` or a newtype struct back
+/// to themselves. A number from `serde_json` with its `arbitrary_precision`
+/// feature is passed on like any other number, or as a `str(v)` of its
+/// digits when it doesn't fit in a `u64`, `i64`, or `f64` exactly; with the
+/// `xml` feature, so is the text content of an XML element:
+///
+/// ```rust
+/// use serde::de::{self, Unexpected, Visitor};
+/// use serde::Deserialize;
+/// use serde_this_or_that::define_coercion;
+///
+/// define_coercion! {
+///     /// De-serialize a percentage, such as `50` or `"50%"`.
+///     pub fn as_percent -> u8, DeserializePercentWithVisitor;
+///     /// De-serialize a percentage, or [`None`] if it is invalid.
+///     pub fn as_opt_percent, DeserializeOptionalPercentWithVisitor;
+///
+///     expecting = "a percentage from 0 to 100";
+///     hint = deserialize_u8;
+///
+///     value {
+///         u64(v) => match v {
+///             0..=100 => Ok(v as u8),
+///             _ => Err(de::Error::invalid_value(
+///                 Unexpected::Unsigned(v),
+///                 &DeserializePercentWithVisitor,
+///             )),
+///         },
+///         str(v) => match v.trim_end_matches('%').parse() {
+///             Ok(n) => DeserializePercentWithVisitor.visit_u64(n),
+///             Err(_) => Err(de::Error::invalid_value(
+///                 Unexpected::Str(v),
+///                 &DeserializePercentWithVisitor,
+///             )),
+///         },
+///         unit => Ok(0),
+///     }
+///
+///     option {
+///         bool(_) => None,
+///         u64(v) => u8::try_from(v).ok().filter(|n| *n <= 100),
+///         str(v) => v.trim_end_matches('%').parse().ok().filter(|n| *n <= 100),
+///         unit => None,
+///     }
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Msg {
+///     #[serde(deserialize_with = "as_percent")]
+///     progress: u8,
+///     #[serde(deserialize_with = "as_opt_percent")]
+///     battery: Option<u8>,
+/// }
+///
+/// let m: Msg = serde_json::from_str(r#"{"progress": "50%", "battery": true}"#).unwrap();
+/// assert_eq!(m.progress, 50);
+/// assert_eq!(m.battery, None);
+/// ```
+///
+/// [`as_u64`]: crate::as_u64
+/// [`as_opt_u64`]: crate::as_opt_u64
+#[macro_export]
+macro_rules! define_coercion {
+    (
+        $(#[$meta:meta])*
+        $vis:vis fn $as_fn:ident -> $ty:ty, $visitor:ident;
+        $(#[$opt_meta:meta])*
+        $opt_vis:vis fn $as_opt_fn:ident, $opt_visitor:ident;
+
+        expecting = $expecting:literal;
+        $(hint = $hint:ident;)?
+
+        value {
+            $($kind:ident $(($v:pat))? => $body:expr),* $(,)?
+        }
+
+        option {
+            $($opt_kind:ident $(($opt_v:pat))? => $opt_body:expr),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis fn $as_fn<'de, D>(deserializer: D) -> ::core::result::Result<$ty, D::Error>
+        where
+            D: $crate::de::Deserializer<'de>,
+        {
+            $crate::__define_coercion_deserialize!(deserializer, $visitor, $($hint)?)
+        }
+
+        $(#[$opt_meta])*
+        $opt_vis fn $as_opt_fn<'de, D>(
+            deserializer: D,
+        ) -> ::core::result::Result<::core::option::Option<$ty>, D::Error>
+        where
+            D: $crate::de::Deserializer<'de>,
+        {
+            $crate::__define_coercion_deserialize!(@opt deserializer, $opt_visitor, $($hint)?)
+        }
+
+        #[doc = concat!(
+            "The [`Visitor`](serde::de::Visitor) used by [`",
+            stringify!($as_fn),
+            "`]."
+        )]
+        #[derive(Clone, Copy, Debug, Default)]
+        $vis struct $visitor;
+
+        impl<'de> $crate::de::Visitor<'de> for $visitor {
+            type Value = $ty;
+
+            fn expecting(
+                &self,
+                formatter: &mut ::core::fmt::Formatter<'_>,
+            ) -> ::core::fmt::Result {
+                formatter.write_str($expecting)
+            }
+
+            $(
+                $crate::__define_coercion_visit!(value $kind ($($v)?) $body);
+            )*
+
+            $crate::__define_coercion_wrappers!($($hint)?);
+        }
+
+        #[doc = concat!(
+            "The [`Visitor`](serde::de::Visitor) used by [`",
+            stringify!($as_opt_fn),
+            "`]."
+        )]
+        #[derive(Clone, Copy, Debug, Default)]
+        $opt_vis struct $opt_visitor;
+
+        impl<'de> $crate::de::Visitor<'de> for $opt_visitor {
+            type Value = ::core::option::Option<$ty>;
+
+            fn expecting(
+                &self,
+                formatter: &mut ::core::fmt::Formatter<'_>,
+            ) -> ::core::fmt::Result {
+                formatter.write_str($expecting)
+            }
+
+            $(
+                $crate::__define_coercion_visit!(option $opt_kind ($($opt_v)?) $opt_body);
+            )*
+
+            $crate::__define_coercion_wrappers!($($hint)?);
+        }
+    };
+}
+
+/// Calls the `deserialize_*` method for [`define_coercion!`]: the `hint`
+/// (or `deserialize_option`) for a format which is not human-readable, with
+/// the `typed` feature, and otherwise `deserialize_any`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __define_coercion_deserialize {
+    ($deserializer:ident, $visitor:expr, $hint:ident) => {
+        if $crate::__private::is_typed(&$deserializer) {
+            $deserializer.$hint($visitor)
+        } else {
+            $deserializer.deserialize_any($visitor)
+        }
+    };
+    (@opt $deserializer:ident, $visitor:expr, $hint:ident) => {
+        $crate::__define_coercion_deserialize!($deserializer, $visitor, deserialize_option)
+    };
+    ($(@opt)? $deserializer:ident, $visitor:expr, ) => {
+        $deserializer.deserialize_any($visitor)
+    };
+}
+
+/// Defines the `visit_none`, `visit_some`, `visit_newtype_struct`, and
+/// `visit_map` methods for [`define_coercion!`]: `None` is treated like a
+/// `null` value, the value inside of a `Some` or a newtype struct is passed
+/// to the visitor, and so is a value which the format wraps in a map (see
+/// [`visit_wrapped_map`](crate::__private::visit_wrapped_map)).
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __define_coercion_wrappers {
+    ($($hint:ident)?) => {
+        fn visit_none<E>(self) -> ::core::result::Result<Self::Value, E>
+        where
+            E: $crate::de::Error,
+        {
+            $crate::de::Visitor::visit_unit(self)
+        }
+
+        fn visit_some<D>(self, deserializer: D) -> ::core::result::Result<Self::Value, D::Error>
+        where
+            D: $crate::de::Deserializer<'de>,
+        {
+            $crate::__define_coercion_deserialize!(deserializer, self, $($hint)?)
+        }
+
+        fn visit_newtype_struct<D>(
+            self,
+            deserializer: D,
+        ) -> ::core::result::Result<Self::Value, D::Error>
+        where
+            D: $crate::de::Deserializer<'de>,
+        {
+            $crate::__define_coercion_deserialize!(deserializer, self, $($hint)?)
+        }
+
+        fn visit_map<A>(self, map: A) -> ::core::result::Result<Self::Value, A::Error>
+        where
+            A: $crate::de::MapAccess<'de>,
+        {
+            $crate::__private::visit_wrapped_map(map, self)
+        }
+    };
+}
+
+/// Defines one `visit_*` method for [`define_coercion!`].
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __define_coercion_visit {
+    ($mode:ident bool ($v:pat) $body:expr) => {
+        $crate::__define_coercion_visit!(@fn $mode visit_bool ($v: bool) $body);
+    };
+    ($mode:ident i64 ($v:pat) $body:expr) => {
+        $crate::__define_coercion_visit!(@fn $mode visit_i64 ($v: i64) $body);
+    };
+    ($mode:ident u64 ($v:pat) $body:expr) => {
+        $crate::__define_coercion_visit!(@fn $mode visit_u64 ($v: u64) $body);
+    };
+    ($mode:ident f64 ($v:pat) $body:expr) => {
+        $crate::__define_coercion_visit!(@fn $mode visit_f64 ($v: f64) $body);
+    };
+    ($mode:ident char ($v:pat) $body:expr) => {
+        $crate::__define_coercion_visit!(@fn $mode visit_char ($v: char) $body);
+    };
+    ($mode:ident str ($v:pat) $body:expr) => {
+        $crate::__define_coercion_visit!(@fn $mode visit_str ($v: &str) $body);
+    };
+    ($mode:ident string ($v:pat) $body:expr) => {
+        $crate::__define_coercion_visit!(
+            @fn $mode visit_string ($v: $crate::__private::String) $body
+        );
+    };
+    ($mode:ident unit () $body:expr) => {
+        $crate::__define_coercion_visit!(@fn $mode visit_unit () $body);
+    };
+    (@fn $mode:ident $method:ident ($($arg:tt)*) $body:expr) => {
+        fn $method<E>(self, $($arg)*) -> ::core::result::Result<Self::Value, E>
+        where
+            E: $crate::de::Error,
+        {
+            $crate::__define_coercion_visit!(@body $mode $body)
+        }
+    };
+    (@body value $body:expr) => {
+        $body
+    };
+    (@body option $body:expr) => {
+        ::core::result::Result::Ok($body)
+    };
+}
@@ -0,0 +1,320 @@
+//! Observability for the coercions done by the helper functions.
+//!
+//! With the `observe` feature enabled, each visitor behind the `as_*` and
+//! `as_opt_*` helpers reports an [`Event`] whenever it produces a value,
+//! describing the kind of input it saw, the output, and whether the
+//! conversion was [lossless](Outcome::Lossless), [lossy](Outcome::Lossy),
+//! [defaulted](Outcome::Defaulted) or [dropped](Outcome::Dropped).
+//!
+//! Events are counted (see [`counts`]), passed to the hook installed with
+//! [`set_hook`] (if any), and, with the `tracing` feature enabled, emitted as
+//! [`tracing`](https://docs.rs/tracing) events with a target of
+//! `serde_this_or_that`.
+//!
+//! ```rust
+//! use serde::Deserialize;
+//! use serde_this_or_that::as_u64;
+//! use serde_this_or_that::observe::{self, Event, Outcome};
+//!
+//! fn report(event: &Event<'_>) {
+//!     if event.outcome != Outcome::Lossless {
+//!         eprintln!("{}: coerced {:?} to {:?}", event.helper, event.input, event.output);
+//!     }
+//! }
+//!
+//! #[derive(Deserialize)]
+//! struct Msg {
+//!     #[serde(deserialize_with = "as_u64")]
+//!     num_attempts: u64,
+//! }
+//!
+//! observe::set_hook(report);
+//!
+//! let before = observe::counts();
+//! let _: Msg = serde_json::from_str(r#"{"num_attempts": ""}"#).unwrap();
+//! assert!(observe::counts().defaulted > before.defaulted);
+//! ```
+
+use core::fmt;
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+
+/// The kind of input a visitor was given.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InputKind {
+    /// A `null` (or unit) value.
+    Null,
+    /// A `bool` value.
+    Bool,
+    /// A signed integer.
+    I64,
+    /// An unsigned integer.
+    U64,
+    /// A floating point number.
+    F64,
+    /// A `char` value.
+    Char,
+    /// A string, which may be empty.
+    Str,
+}
+
+/// How an input was converted to the output value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    /// The output represents the input exactly, such as `"3"` to `3`.
+    Lossless,
+    /// The output only approximates the input, such as `2.5` rounded to `3`.
+    Lossy,
+    /// The input carried no value, such as `null` or `""`, and a default
+    /// (such as zero, or [`None`]) was used in its place.
+    Defaulted,
+    /// The input was invalid, and was discarded in favor of [`None`].
+    Dropped,
+}
+
+/// A single coercion, as reported by one of the crate's visitors.
+#[derive(Clone, Copy)]
+pub struct Event<'a> {
+    /// The name of the helper function, such as `"as_u64"`.
+    pub helper: &'static str,
+    /// The kind of input.
+    pub input: InputKind,
+    /// How the input was converted.
+    pub outcome: Outcome,
+    /// The output value.
+    pub output: &'a dyn fmt::Debug,
+}
+
+impl fmt::Debug for Event<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Event")
+            .field("helper", &self.helper)
+            .field("input", &self.input)
+            .field("outcome", &self.outcome)
+            .field("output", self.output)
+            .finish()
+    }
+}
+
+/// The number of events seen for each [`Outcome`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Counts {
+    /// The number of [`Outcome::Lossless`] events.
+    pub lossless: usize,
+    /// The number of [`Outcome::Lossy`] events.
+    pub lossy: usize,
+    /// The number of [`Outcome::Defaulted`] events.
+    pub defaulted: usize,
+    /// The number of [`Outcome::Dropped`] events.
+    pub dropped: usize,
+}
+
+/// A hook which is called for every [`Event`].
+pub type Hook = fn(&Event<'_>);
+
+static HOOK: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+static COUNTS: [AtomicUsize; 4] = [
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+    AtomicUsize::new(0),
+];
+
+/// Installs a global `hook` which is called for every [`Event`], replacing
+/// any previous one.
+pub fn set_hook(hook: Hook) {
+    HOOK.store(hook as *mut (), Ordering::Release);
+}
+
+/// Removes the hook installed with [`set_hook`], if any.
+pub fn remove_hook() {
+    HOOK.store(ptr::null_mut(), Ordering::Release);
+}
+
+/// Returns the number of events seen so far for each [`Outcome`].
+pub fn counts() -> Counts {
+    let load = |outcome: Outcome| COUNTS[outcome as usize].load(Ordering::Relaxed);
+    Counts {
+        lossless: load(Outcome::Lossless),
+        lossy: load(Outcome::Lossy),
+        defaulted: load(Outcome::Defaulted),
+        dropped: load(Outcome::Dropped),
+    }
+}
+
+/// Resets the counts returned by [`counts`] to zero.
+pub fn reset_counts() {
+    for count in &COUNTS {
+        count.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Reports an event; this is called by the `observed!` macro.
+#[inline]
+pub(crate) fn emit(
+    helper: &'static str,
+    input: InputKind,
+    outcome: Outcome,
+    output: &dyn fmt::Debug,
+) {
+    COUNTS[outcome as usize].fetch_add(1, Ordering::Relaxed);
+
+    #[cfg(feature = "tracing")]
+    match outcome {
+        Outcome::Lossless => tracing::trace!(
+            target: "serde_this_or_that",
+            helper,
+            ?input,
+            ?outcome,
+            ?output,
+            "coerced value"
+        ),
+        _ => tracing::debug!(
+            target: "serde_this_or_that",
+            helper,
+            ?input,
+            ?outcome,
+            ?output,
+            "coerced value"
+        ),
+    }
+
+    let hook = HOOK.load(Ordering::Acquire);
+    if !hook.is_null() {
+        // SAFETY: the only non-null values stored in `HOOK` are `Hook`
+        // function pointers, in `set_hook`.
+        let hook = unsafe { core::mem::transmute::<*mut (), Hook>(hook) };
+        hook(&Event {
+            helper,
+            input,
+            outcome,
+            output,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{as_bool, as_f64, as_opt_u64, as_u64};
+    use serde::Deserialize;
+    use std::cell::RefCell;
+    use std::format;
+    use std::string::String;
+    use std::vec::Vec;
+
+    std::thread_local! {
+        static EVENTS: RefCell<Vec<(&'static str, InputKind, Outcome, String)>> =
+            const { RefCell::new(Vec::new()) };
+    }
+
+    /// Records events for the current thread only, as tests run in parallel.
+    fn record(event: &Event<'_>) {
+        EVENTS.with(|events| {
+            events.borrow_mut().push((
+                event.helper,
+                event.input,
+                event.outcome,
+                format!("{:?}", event.output),
+            ))
+        });
+    }
+
+    fn take_events() -> Vec<(&'static str, InputKind, Outcome, String)> {
+        EVENTS.with(|events| events.borrow_mut().drain(..).collect())
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct TestObserveStruct {
+        #[serde(deserialize_with = "as_u64")]
+        count: u64,
+        #[serde(deserialize_with = "as_f64")]
+        grade: f64,
+        #[serde(deserialize_with = "as_bool")]
+        active: bool,
+        #[serde(deserialize_with = "as_opt_u64")]
+        limit: Option<u64>,
+    }
+
+    #[test]
+    fn test_events_for_each_outcome() {
+        set_hook(record);
+
+        let json = r#"{"count": 2.5, "grade": "", "active": "yes", "limit": "x"}"#;
+        let _: TestObserveStruct = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            take_events(),
+            [
+                ("as_u64", InputKind::F64, Outcome::Lossy, "3".to_owned()),
+                (
+                    "as_f64",
+                    InputKind::Str,
+                    Outcome::Defaulted,
+                    "0.0".to_owned()
+                ),
+                (
+                    "as_bool",
+                    InputKind::Str,
+                    Outcome::Lossless,
+                    "true".to_owned()
+                ),
+                (
+                    "as_opt_u64",
+                    InputKind::Str,
+                    Outcome::Dropped,
+                    "None".to_owned()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_events_for_lossless_and_null_inputs() {
+        set_hook(record);
+
+        let json = r#"{"count": "7", "grade": 1, "active": null, "limit": null}"#;
+        let _: TestObserveStruct = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            take_events(),
+            [
+                ("as_u64", InputKind::Str, Outcome::Lossless, "7".to_owned()),
+                (
+                    "as_f64",
+                    InputKind::U64,
+                    Outcome::Lossless,
+                    "1.0".to_owned()
+                ),
+                (
+                    "as_bool",
+                    InputKind::Null,
+                    Outcome::Defaulted,
+                    "false".to_owned()
+                ),
+                (
+                    "as_opt_u64",
+                    InputKind::Null,
+                    Outcome::Defaulted,
+                    "None".to_owned()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_counts() {
+        let before = counts();
+
+        let json = r#"{"count": 1, "grade": 1.5, "active": "maybe", "limit": true}"#;
+        let _: TestObserveStruct = serde_json::from_str(json).unwrap();
+
+        // Other tests may run at the same time, so only check for increases.
+        let after = counts();
+        assert!(after.lossless >= before.lossless + 2);
+        assert!(after.defaulted > before.defaulted);
+        assert!(after.dropped > before.dropped);
+    }
+}
@@ -0,0 +1,402 @@
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::fmt;
+
+use crate::de::{self, Deserialize, Deserializer};
+use crate::de_coerce::Coerce;
+use crate::lenient::Lenient;
+use crate::ser::{Serialize, Serializer};
+use crate::typed::is_typed;
+
+/// A field of a *patch*, such as the body of a JSON Merge Patch request,
+/// which distinguishes a missing field from a `null` value.
+///
+/// A missing field means the value should be left unchanged, while a `null`
+/// value means it should be cleared. Any other value is de-serialized with
+/// the same rules as the crate's helper functions, such as
+/// [`as_u64`](crate::as_u64) for a `Patch<u64>`.
+///
+/// The field needs to be annotated with `#[serde(default)]`, so that it is
+/// [`Patch::Missing`] when it is not present:
+///
+/// ```rust
+/// use serde::Deserialize;
+/// use serde_this_or_that::Patch;
+///
+/// #[derive(Deserialize)]
+/// struct UserPatch {
+///     #[serde(default)]
+///     age: Patch<u64>,
+///     #[serde(default)]
+///     nickname: Patch<String>,
+/// }
+///
+/// let p: UserPatch = serde_json::from_str(r#"{"age": "42", "nickname": null}"#).unwrap();
+/// assert_eq!(p.age, Patch::Value(42));
+/// assert_eq!(p.nickname, Patch::Null);
+///
+/// let p: UserPatch = serde_json::from_str("{}").unwrap();
+/// assert_eq!(p.age, Patch::Missing);
+/// ```
+///
+/// When serialized, [`Patch::Null`] is written as `null`, and so is
+/// [`Patch::Missing`], unless the field is also annotated with
+/// `#[serde(skip_serializing_if = "Patch::is_missing")]`.
+///
+/// With the `typed` feature, for a format which is not human-readable, such
+/// as `bincode`, a `Patch<T>` is read and written as an `Option<T>`, so that
+/// [`Patch::Missing`] is read back as [`Patch::Null`].
+///
+/// `Patch<T>` is supported for any type which implements
+/// [`Coerce`](crate::Coerce). For a field of type `Option<Option<T>>`, see
+/// [`as_patch`] instead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Patch<T> {
+    /// The field is not present, and the value should be left unchanged.
+    Missing,
+    /// The field is `null`, and the value should be cleared.
+    Null,
+    /// The field has a value, which should replace the current one.
+    Value(T),
+}
+
+impl<T> Patch<T> {
+    /// Returns `true` if the field is not present.
+    pub const fn is_missing(&self) -> bool {
+        matches!(self, Patch::Missing)
+    }
+
+    /// Returns `true` if the field is `null`.
+    pub const fn is_null(&self) -> bool {
+        matches!(self, Patch::Null)
+    }
+
+    /// Returns `true` if the field has a value.
+    pub const fn is_value(&self) -> bool {
+        matches!(self, Patch::Value(_))
+    }
+
+    /// Converts from `&Patch<T>` to `Patch<&T>`.
+    pub const fn as_ref(&self) -> Patch<&T> {
+        match self {
+            Patch::Missing => Patch::Missing,
+            Patch::Null => Patch::Null,
+            Patch::Value(v) => Patch::Value(v),
+        }
+    }
+
+    /// Returns the value as an `Option<Option<T>>`: [`None`] if the field
+    /// is not present, and `Some(None)` if it is `null`.
+    pub fn into_option(self) -> Option<Option<T>> {
+        match self {
+            Patch::Missing => None,
+            Patch::Null => Some(None),
+            Patch::Value(v) => Some(Some(v)),
+        }
+    }
+
+    /// Applies the patch to `target`: a value replaces it, a `null` clears
+    /// it, and a missing field leaves it unchanged.
+    pub fn apply(self, target: &mut Option<T>) {
+        match self {
+            Patch::Missing => {}
+            Patch::Null => *target = None,
+            Patch::Value(v) => *target = Some(v),
+        }
+    }
+}
+
+impl<T> Default for Patch<T> {
+    /// Returns [`Patch::Missing`].
+    fn default() -> Self {
+        Patch::Missing
+    }
+}
+
+impl<T> From<Option<Option<T>>> for Patch<T> {
+    fn from(value: Option<Option<T>>) -> Self {
+        match value {
+            None => Patch::Missing,
+            Some(None) => Patch::Null,
+            Some(Some(v)) => Patch::Value(v),
+        }
+    }
+}
+
+impl<T> From<Patch<T>> for Option<Option<T>> {
+    fn from(value: Patch<T>) -> Self {
+        value.into_option()
+    }
+}
+
+impl<T: Serialize> Serialize for Patch<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Patch::Missing | Patch::Null => serializer.serialize_none(),
+            Patch::Value(v) => serializer.serialize_some(v),
+        }
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Patch<T>
+where
+    T: Coerce,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if is_typed(&deserializer) {
+            return Option::<Lenient<T>>::deserialize(deserializer).map(|opt| match opt {
+                Some(Lenient(v)) => Patch::Value(v),
+                None => Patch::Null,
+            });
+        }
+
+        deserializer.deserialize_any(PatchVisitor(T::Visitor::default()))
+    }
+}
+
+/// De-serialize a field of a *patch* as an `Option<Option<T>>`, for any type
+/// `T` which implements [`Coerce`]: [`None`] if the field is not present,
+/// `Some(None)` if it is `null`, and otherwise `Some` with the value.
+///
+/// The field needs to be annotated with `#[serde(default)]`, so that it is
+/// [`None`] when it is not present:
+///
+/// ```rust
+/// use serde::Deserialize;
+/// use serde_this_or_that::as_patch;
+///
+/// #[derive(Deserialize)]
+/// struct UserPatch {
+///     #[serde(default, deserialize_with = "as_patch")]
+///     age: Option<Option<u64>>,
+/// }
+///
+/// let p: UserPatch = serde_json::from_str(r#"{"age": "42"}"#).unwrap();
+/// assert_eq!(p.age, Some(Some(42)));
+///
+/// let p: UserPatch = serde_json::from_str(r#"{"age": null}"#).unwrap();
+/// assert_eq!(p.age, Some(None));
+///
+/// let p: UserPatch = serde_json::from_str("{}").unwrap();
+/// assert_eq!(p.age, None);
+/// ```
+///
+/// With the `typed` feature, for a format which is not human-readable, the
+/// field is read as an `Option<T>`, as for a [`Patch<T>`].
+///
+/// # Errors
+/// Returns an error if the value is neither `null` nor one which the
+/// [`Visitor`](Coerce::Visitor) of `T` accepts.
+///
+pub fn as_patch<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Coerce,
+{
+    Patch::<T>::deserialize(deserializer).map(Patch::into_option)
+}
+
+/// A visitor which returns [`Patch::Null`] for a `null` value, and passes
+/// anything else on to the inner visitor.
+struct PatchVisitor<V>(V);
+
+impl<'de, V> de::Visitor<'de> for PatchVisitor<V>
+where
+    V: de::Visitor<'de>,
+{
+    type Value = Patch<V::Value>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.expecting(formatter)?;
+        formatter.write_str(", or null")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_bool(v).map(Patch::Value)
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_i64(v).map(Patch::Value)
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_u64(v).map(Patch::Value)
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_f64(v).map(Patch::Value)
+    }
+
+    fn visit_char<E>(self, v: char) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_char(v).map(Patch::Value)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_str(v).map(Patch::Value)
+    }
+
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_borrowed_str(v).map(Patch::Value)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_string(v).map(Patch::Value)
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Patch::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Patch::Null)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        crate::de_wrapped::visit_wrapped_map(map, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    struct TestPatchStruct {
+        #[serde(default, skip_serializing_if = "Patch::is_missing")]
+        count: Patch<u64>,
+        #[serde(default, skip_serializing_if = "Patch::is_missing")]
+        active: Patch<bool>,
+        #[serde(default, skip_serializing_if = "Patch::is_missing")]
+        name: Patch<String>,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct TestAsPatchStruct {
+        #[serde(default, deserialize_with = "as_patch")]
+        count: Option<Option<u64>>,
+        #[serde(default, deserialize_with = "as_patch")]
+        grade: Option<Option<f64>>,
+    }
+
+    #[test]
+    fn test_patch_three_states() {
+        let json = r#"{"count": "3", "active": null}"#;
+        let deserialized: TestPatchStruct = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            deserialized,
+            TestPatchStruct {
+                count: Patch::Value(3),
+                active: Patch::Null,
+                name: Patch::Missing,
+            }
+        );
+    }
+
+    #[test]
+    fn test_patch_uses_coercion_rules() {
+        let json = r#"{"count": 2.5, "active": "yes", "name": 7}"#;
+        let deserialized: TestPatchStruct = serde_json::from_str(json).unwrap();
+
+        assert_eq!(deserialized.count, Patch::Value(3));
+        assert_eq!(deserialized.active, Patch::Value(true));
+        assert_eq!(deserialized.name, Patch::Value(String::from("7")));
+
+        let json = r#"{"count": "x"}"#;
+        let deserialized = serde_json::from_str::<TestPatchStruct>(json);
+        assert!(deserialized.is_err());
+    }
+
+    #[test]
+    fn test_patch_round_trip() {
+        for json in [r#"{"count":3,"active":null}"#, r#"{"name":"x"}"#, "{}"] {
+            let deserialized: TestPatchStruct = serde_json::from_str(json).unwrap();
+            assert_eq!(serde_json::to_string(&deserialized).unwrap(), json);
+        }
+    }
+
+    #[test]
+    fn test_as_patch() {
+        for (json, count, grade) in [
+            (
+                r#"{"count": "3", "grade": null}"#,
+                Some(Some(3)),
+                Some(None),
+            ),
+            (r#"{"grade": "81.5"}"#, None, Some(Some(81.5))),
+            ("{}", None, None),
+        ] {
+            let deserialized: TestAsPatchStruct = serde_json::from_str(json).unwrap();
+            assert_eq!(deserialized, TestAsPatchStruct { count, grade }, "{json}");
+        }
+    }
+
+    #[test]
+    fn test_patch_apply() {
+        let mut target = Some(1);
+
+        Patch::Missing.apply(&mut target);
+        assert_eq!(target, Some(1));
+
+        Patch::Value(2).apply(&mut target);
+        assert_eq!(target, Some(2));
+
+        Patch::Null.apply(&mut target);
+        assert_eq!(target, None);
+    }
+}
@@ -0,0 +1,458 @@
+//! JSON Schema functions describing the input accepted by each helper.
+//!
+//! When deriving [`JsonSchema`] for a struct, a field such as
+//! `num_attempts: u64` is described as an `integer`, even with
+//! `deserialize_with = "as_u64"`, so clients validating against the schema
+//! would reject a string such as `"3"`. The functions in this module describe
+//! everything the respective helper accepts instead, and can be used with
+//! `#[schemars(schema_with = "...")]`:
+//!
+//! ```rust
+//! use schemars::JsonSchema;
+//! use serde::Deserialize;
+//! use serde_this_or_that::{as_bool, as_u64, schema};
+//!
+//! #[derive(Deserialize, JsonSchema)]
+//! struct MyStruct {
+//!     #[serde(deserialize_with = "as_u64")]
+//!     #[schemars(schema_with = "schema::as_u64")]
+//!     num_attempts: u64,
+//!     #[serde(deserialize_with = "as_bool")]
+//!     #[schemars(schema_with = "schema::as_bool")]
+//!     is_active: bool,
+//! }
+//!
+//! let schema = schemars::schema_for!(MyStruct);
+//! let num_attempts = &schema.as_value()["properties"]["num_attempts"];
+//!
+//! assert_eq!(num_attempts["anyOf"][0]["type"], "number");
+//! assert_eq!(num_attempts["anyOf"][1]["type"], "string");
+//! assert_eq!(num_attempts["anyOf"][2]["type"], "null");
+//! ```
+//!
+//! The `as_opt` functions describe the same input as their counterparts, as
+//! the `as_opt` helpers only differ in how they handle a value which cannot
+//! be converted.
+//!
+//! For the generic helpers, such as [`as_json_str`](crate::as_json_str) and
+//! [`as_one_or_many`](crate::as_one_or_many), the function takes the type of
+//! the value as well, such as
+//! `#[schemars(schema_with = "schema::as_one_or_many::<Item>")]`.
+//!
+//! The helpers which take an argument are described by the function for the
+//! helper they are based on; for example, [`as_bytes()`] also describes
+//! [`as_bytes_in_order`](crate::as_bytes_in_order), and [`as_string()`]
+//! describes [`as_string_with`](crate::as_string_with). The other helpers,
+//! such as [`as_patch`](crate::as_patch) and [`as_map_of`](crate::as_map_of),
+//! are not covered.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+
+use crate::coerce::{FALSY_PHRASES, TRUTHY_PHRASES};
+
+/// Matches a string containing a number (such as `"3"`, `"-2.5"`, or
+/// `"1e3"`), or an empty string.
+const NUMBER_PATTERN: &str = r"^([+-]?([0-9]+\.?[0-9]*|\.[0-9]+)([eE][+-]?[0-9]+)?)?$";
+
+/// Describes the input accepted by [`as_u64`](crate::as_u64): a number, a
+/// string containing a number, or `null`.
+///
+/// A float is rounded to the nearest integer, and a negative float or string
+/// (such as `-2.5` or `"-7"`) results in `0`. A negative integer is an error,
+/// but is still valid for the schema, as JSON Schema cannot tell it apart
+/// from a float such as `-7.0`.
+pub fn as_u64(_: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+        "anyOf": [
+            { "type": "number" },
+            { "type": "string", "pattern": NUMBER_PATTERN },
+            { "type": "null" }
+        ]
+    })
+}
+
+/// Describes the input accepted by [`as_opt_u64`](crate::as_opt_u64), which
+/// is the same as for [`as_u64()`].
+pub fn as_opt_u64(generator: &mut SchemaGenerator) -> Schema {
+    as_u64(generator)
+}
+
+/// Describes the input accepted by [`as_i64`](crate::as_i64): a number, a
+/// string containing a number, or `null`.
+///
+/// A float is rounded to the nearest integer.
+pub fn as_i64(_: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+        "anyOf": [
+            { "type": "number" },
+            { "type": "string", "pattern": NUMBER_PATTERN },
+            { "type": "null" }
+        ]
+    })
+}
+
+/// Describes the input accepted by [`as_opt_i64`](crate::as_opt_i64), which
+/// is the same as for [`as_i64()`].
+pub fn as_opt_i64(generator: &mut SchemaGenerator) -> Schema {
+    as_i64(generator)
+}
+
+/// Describes the input accepted by [`as_f64`](crate::as_f64): a number, a
+/// string containing a number, or `null`.
+pub fn as_f64(_: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+        "anyOf": [
+            { "type": "number", "format": "double" },
+            { "type": "string", "pattern": NUMBER_PATTERN },
+            { "type": "null" }
+        ]
+    })
+}
+
+/// Describes the input accepted by [`as_opt_f64`](crate::as_opt_f64), which
+/// is the same as for [`as_f64()`].
+pub fn as_opt_f64(generator: &mut SchemaGenerator) -> Schema {
+    as_f64(generator)
+}
+
+/// Describes the input accepted by [`as_bool`](crate::as_bool): a boolean,
+/// a *zero* or a *one*, one of the "truthy" or "falsy" strings, or `null`.
+///
+/// The strings are matched *case-insensitively*, so the schema lists their
+/// lower case, upper case, and capitalized spellings (such as `yes`, `YES`,
+/// and `Yes`), as well as an empty string. Although `as_bool` reads any other
+/// string as `false`, these are left out, as they are likely a mistake.
+pub fn as_bool(_: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+        "anyOf": [
+            { "type": "boolean" },
+            { "enum": [0, 1] },
+            { "type": "string", "enum": bool_strings() },
+            { "type": "null" }
+        ]
+    })
+}
+
+/// Describes the input accepted by [`as_opt_bool`](crate::as_opt_bool),
+/// which is the same as for [`as_bool()`].
+pub fn as_opt_bool(generator: &mut SchemaGenerator) -> Schema {
+    as_bool(generator)
+}
+
+/// Describes the input accepted by [`as_char`](crate::as_char): a string,
+/// an integer *code point*, or `null`.
+pub fn as_char(_: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+        "anyOf": [
+            { "type": "string" },
+            { "type": "integer", "minimum": 0, "maximum": 0x10FFFF },
+            { "type": "null" }
+        ]
+    })
+}
+
+/// Describes the input accepted by [`as_opt_char`](crate::as_opt_char),
+/// which is the same as for [`as_char()`].
+pub fn as_opt_char(generator: &mut SchemaGenerator) -> Schema {
+    as_char(generator)
+}
+
+/// Describes the input accepted by [`as_string`](crate::as_string): a
+/// string, a number, a boolean, or `null`.
+pub fn as_string(_: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+        "type": ["string", "number", "boolean", "null"]
+    })
+}
+
+/// Describes the input accepted by [`as_opt_string`](crate::as_opt_string),
+/// which is the same as for [`as_string()`].
+pub fn as_opt_string(generator: &mut SchemaGenerator) -> Schema {
+    as_string(generator)
+}
+
+/// Describes the input accepted by [`as_raw_string`](crate::as_raw_string):
+/// a string.
+pub fn as_raw_string(_: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+        "type": "string"
+    })
+}
+
+/// Describes the input accepted by
+/// [`as_opt_raw_string`](crate::as_opt_raw_string): a string, or `null`.
+pub fn as_opt_raw_string(_: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+        "type": ["string", "null"]
+    })
+}
+
+/// Describes the input accepted by [`as_cow_str`](crate::as_cow_str), which
+/// is the same as for [`as_string()`].
+pub fn as_cow_str(generator: &mut SchemaGenerator) -> Schema {
+    as_string(generator)
+}
+
+/// Describes the input accepted by [`as_bytes`](crate::as_bytes): a string
+/// in one of the [`ByteEncoding`](crate::ByteEncoding)s, an array of bytes,
+/// or `null`.
+pub fn as_bytes(_: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+        "anyOf": [
+            { "type": "string" },
+            {
+                "type": "array",
+                "items": { "type": "integer", "minimum": 0, "maximum": 255 }
+            },
+            { "type": "null" }
+        ]
+    })
+}
+
+/// Describes the input accepted by [`as_opt_bytes`](crate::as_opt_bytes),
+/// which is the same as for [`as_bytes()`].
+pub fn as_opt_bytes(generator: &mut SchemaGenerator) -> Schema {
+    as_bytes(generator)
+}
+
+/// Describes the input accepted by [`as_json_str`](crate::as_json_str) for
+/// a value of type `T`: the value itself, a string containing it as JSON,
+/// or `null`.
+#[cfg(feature = "serde_json")]
+pub fn as_json_str<T: JsonSchema>(generator: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+        "anyOf": [
+            generator.subschema_for::<T>(),
+            { "type": "string" },
+            { "type": "null" }
+        ]
+    })
+}
+
+/// Describes the input accepted by
+/// [`as_opt_json_str`](crate::as_opt_json_str), which is the same as for
+/// [`as_json_str()`].
+#[cfg(feature = "serde_json")]
+pub fn as_opt_json_str<T: JsonSchema>(generator: &mut SchemaGenerator) -> Schema {
+    as_json_str::<T>(generator)
+}
+
+/// Describes the input accepted by [`as_one_or_many`](crate::as_one_or_many)
+/// for a vector of `T`: a single value, an array of values, or `null`.
+///
+/// ```rust
+/// use schemars::JsonSchema;
+/// use serde::Deserialize;
+/// use serde_this_or_that::{as_one_or_many, schema};
+///
+/// #[derive(Deserialize, JsonSchema)]
+/// struct Order {
+///     #[serde(deserialize_with = "as_one_or_many")]
+///     #[schemars(schema_with = "schema::as_one_or_many::<u64>")]
+///     item_ids: Vec<u64>,
+/// }
+///
+/// let schema = schemars::schema_for!(Order);
+/// let item_ids = &schema.as_value()["properties"]["item_ids"];
+///
+/// assert_eq!(item_ids["anyOf"][0]["type"], "integer");
+/// assert_eq!(item_ids["anyOf"][1]["type"], "array");
+/// ```
+pub fn as_one_or_many<T: JsonSchema>(generator: &mut SchemaGenerator) -> Schema {
+    let item = generator.subschema_for::<T>();
+    json_schema!({
+        "anyOf": [
+            item.clone(),
+            { "type": "array", "items": item },
+            { "type": "null" }
+        ]
+    })
+}
+
+/// Returns the spellings of the "truthy" and "falsy" strings, and an empty
+/// string.
+fn bool_strings() -> Vec<String> {
+    let phrases = ["1", "T"]
+        .iter()
+        .chain(TRUTHY_PHRASES)
+        .chain(["0", "F"].iter())
+        .chain(FALSY_PHRASES);
+
+    let mut strings = Vec::new();
+    for phrase in phrases {
+        let lower = phrase.to_ascii_lowercase();
+        let capitalized = phrase[..1].to_string() + &lower[1..];
+        for s in [lower, phrase.to_string(), capitalized] {
+            if !strings.contains(&s) {
+                strings.push(s);
+            }
+        }
+    }
+    strings.push(String::new());
+    strings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::de::IntoDeserializer;
+    use schemars::JsonSchema;
+    use serde::Deserialize;
+    use serde_json::Value;
+
+    #[derive(Deserialize, JsonSchema)]
+    #[allow(dead_code)]
+    struct Example {
+        #[serde(deserialize_with = "crate::as_u64")]
+        #[schemars(schema_with = "as_u64")]
+        num_attempts: u64,
+        #[serde(default, deserialize_with = "crate::as_opt_i64")]
+        #[schemars(schema_with = "as_opt_i64")]
+        confidence: Option<i64>,
+        #[serde(deserialize_with = "crate::as_bool")]
+        #[schemars(schema_with = "as_bool")]
+        is_active: bool,
+    }
+
+    /// Returns true if `value` is valid for a single branch of `schema`,
+    /// enough for the schemas in this module.
+    fn accepts(schema: &Value, value: &Value) -> bool {
+        if let Some(branches) = schema["anyOf"].as_array() {
+            return branches.iter().any(|s| accepts(s, value));
+        }
+        if let Some(values) = schema["enum"].as_array() {
+            return values.contains(value);
+        }
+        let types: Vec<&str> = match &schema["type"] {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+            _ => return true,
+        };
+        types.into_iter().any(|t| match (t, value) {
+            ("null", Value::Null) | ("boolean", Value::Bool(_)) => true,
+            ("number", Value::Number(_)) => true,
+            ("integer", Value::Number(n)) => n.as_i64().is_some_and(|n| {
+                schema["minimum"].as_i64().is_none_or(|min| n >= min)
+                    && schema["maximum"].as_i64().is_none_or(|max| n <= max)
+            }),
+            ("array", Value::Array(items)) => items.iter().all(|v| accepts(&schema["items"], v)),
+            ("string", Value::String(s)) => match schema["pattern"].as_str() {
+                Some(pattern) => regex::Regex::new(pattern).unwrap().is_match(s),
+                None => true,
+            },
+            _ => false,
+        })
+    }
+
+    #[test]
+    fn test_derive() {
+        let schema = schemars::schema_for!(Example);
+        let properties = &schema.as_value()["properties"];
+
+        assert_eq!(
+            properties["num_attempts"],
+            as_u64(&mut SchemaGenerator::default()).to_value()
+        );
+        // With `#[serde(default)]`, the schema also has a `default` value.
+        assert_eq!(
+            properties["confidence"]["anyOf"],
+            as_i64(&mut SchemaGenerator::default()).to_value()["anyOf"]
+        );
+        assert_eq!(
+            properties["is_active"],
+            as_bool(&mut SchemaGenerator::default()).to_value()
+        );
+    }
+
+    fn assert_accepts(schema: fn(&mut SchemaGenerator) -> Schema, values: &[Value]) {
+        let schema = schema(&mut SchemaGenerator::default()).to_value();
+        for value in values {
+            assert!(accepts(&schema, value), "{value} for {schema}");
+        }
+    }
+
+    #[test]
+    fn test_accepted_input_is_valid() {
+        assert_accepts(
+            as_u64,
+            &[3.into(), "3".into(), "2.5".into(), "".into(), Value::Null],
+        );
+        // Floats are rounded, and negative ones result in `0`.
+        assert_accepts(as_u64, &[2.5.into(), (-2.5).into(), "-7".into()]);
+        assert_accepts(as_i64, &[(-2.5).into()]);
+        assert_accepts(
+            as_i64,
+            &[(-3).into(), "-1e3".into(), ".5".into(), Value::Null],
+        );
+        assert_accepts(
+            as_f64,
+            &[1.5.into(), "-0.25".into(), "".into(), Value::Null],
+        );
+        assert_accepts(
+            as_bool,
+            &[true.into(), 1.into(), "yes".into(), "OFF".into(), "".into()],
+        );
+        assert_accepts(as_char, &["Y".into(), 89.into(), Value::Null]);
+        assert_accepts(
+            as_string,
+            &["x".into(), 1.5.into(), false.into(), Value::Null],
+        );
+        assert_accepts(as_cow_str, &["x".into(), 1.5.into(), Value::Null]);
+        assert_accepts(as_opt_raw_string, &["02134".into(), Value::Null]);
+        assert_accepts(
+            as_bytes,
+            &["cafe".into(), vec![0xca, 0xfe].into(), Value::Null],
+        );
+        assert_accepts(
+            as_one_or_many::<u64>,
+            &[1.into(), vec![1, 2].into(), Value::Null],
+        );
+        assert_accepts(
+            as_json_str::<Vec<u64>>,
+            &["[1,2]".into(), vec![1, 2].into(), Value::Null],
+        );
+    }
+
+    #[test]
+    fn test_rejected_input_is_invalid() {
+        let u64_schema = as_u64(&mut SchemaGenerator::default()).to_value();
+        assert!(!accepts(&u64_schema, &"A+".into()));
+        assert!(!accepts(&u64_schema, &true.into()));
+
+        for s in ["1.2.3", "1e", "-", " 3", "0x10"] {
+            assert!(!accepts(&u64_schema, &s.into()), "{s}");
+        }
+
+        let raw_schema = as_raw_string(&mut SchemaGenerator::default()).to_value();
+        assert!(!accepts(&raw_schema, &3.into()));
+        assert!(!accepts(&raw_schema, &Value::Null));
+
+        let bytes_schema = as_bytes(&mut SchemaGenerator::default()).to_value();
+        assert!(!accepts(&bytes_schema, &vec![256].into()));
+        assert!(!accepts(&bytes_schema, &3.into()));
+
+        let many_schema = as_one_or_many::<u64>(&mut SchemaGenerator::default()).to_value();
+        assert!(!accepts(&many_schema, &"x".into()));
+        assert!(!accepts(&many_schema, &vec![Value::from("x")].into()));
+
+        let bool_schema = as_bool(&mut SchemaGenerator::default()).to_value();
+        assert!(!accepts(&bool_schema, &2.into()));
+        assert!(!accepts(&bool_schema, &"maybe".into()));
+    }
+
+    #[test]
+    fn test_bool_strings_are_coerced() {
+        for s in bool_strings() {
+            let expected = !s.is_empty();
+            let result: Option<bool> = crate::as_opt_bool(s.as_str().into_deserializer())
+                .map_err(|e: serde::de::value::Error| e)
+                .unwrap();
+            assert_eq!(result.is_some(), expected, "{s:?}");
+        }
+        assert!(bool_strings().contains(&"Yes".to_string()));
+        assert!(bool_strings().contains(&"ng".to_string()));
+    }
+}
@@ -0,0 +1,590 @@
+use core::fmt::{self, Display, LowerExp};
+
+use crate::ser::{
+    Serialize, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+    SerializeTupleStruct, SerializeTupleVariant, Serializer,
+};
+
+/// A [`Serializer`] which wraps another one, and writes every number and
+/// boolean as a string, such as `"3"`, `"1.5"`, or `"true"`.
+///
+/// This is the counterpart of the helper functions, for a consumer which
+/// expects every value to be a string. It applies to the values inside of
+/// structs, maps, sequences, options, and enums as well; strings, `null`
+/// values, and bytes are written as they are.
+///
+/// The strings for `true` and `false` can be set with
+/// [`with_bools`](StringifySerializer::with_bools):
+///
+/// ```rust
+/// use serde::Serialize;
+/// use serde_this_or_that::StringifySerializer;
+///
+/// #[derive(Serialize)]
+/// struct Order {
+///     id: u64,
+///     total: f64,
+///     paid: bool,
+///     notes: Option<String>,
+/// }
+///
+/// let order = Order { id: 3, total: 1.5, paid: true, notes: None };
+///
+/// let mut out = Vec::new();
+/// let mut ser = serde_json::Serializer::new(&mut out);
+/// order.serialize(StringifySerializer::new(&mut ser).with_bools("Y", "N")).unwrap();
+///
+/// assert_eq!(
+///     String::from_utf8(out).unwrap(),
+///     r#"{"id":"3","total":"1.5","paid":"Y","notes":null}"#
+/// );
+/// ```
+///
+/// Floats are written in decimal notation by default, in full: `1e300` is
+/// written as a `1` followed by 300 zeros. Use
+/// [`with_floats`](StringifySerializer::with_floats) to write them in
+/// scientific notation instead, such as `"1e300"`.
+///
+/// For a single field, see [`stringify()`].
+#[derive(Clone, Copy, Debug)]
+pub struct StringifySerializer<S> {
+    inner: S,
+    format: Format,
+}
+
+/// How a [`StringifySerializer`] writes a float.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum FloatFormat {
+    /// In decimal notation, such as `"1.5"` or `"0.000001"`. A large or
+    /// small float is written out in full.
+    #[default]
+    Decimal,
+    /// In scientific notation, such as `"1.5e0"` or `"1e-6"`.
+    Scientific,
+    /// In decimal notation, unless the float is at least `1e21`, or less
+    /// than `1e-6` (and not zero), as JavaScript does; for example, `"1.5"`
+    /// and `"1e300"`.
+    Auto,
+}
+
+/// The strings which values are written as.
+#[derive(Clone, Copy, Debug)]
+struct Format {
+    bools: [&'static str; 2],
+    floats: FloatFormat,
+}
+
+impl<S> StringifySerializer<S> {
+    /// Wraps the `inner` serializer, writing booleans as `"true"` and
+    /// `"false"`.
+    pub const fn new(inner: S) -> Self {
+        Self {
+            inner,
+            format: Format {
+                bools: ["false", "true"],
+                floats: FloatFormat::Decimal,
+            },
+        }
+    }
+
+    /// Writes booleans as `true_str` and `false_str` instead, such as `"1"`
+    /// and `"0"`, or `"Y"` and `"N"`.
+    #[must_use]
+    pub const fn with_bools(mut self, true_str: &'static str, false_str: &'static str) -> Self {
+        self.format.bools = [false_str, true_str];
+        self
+    }
+
+    /// Writes floats in the given [`FloatFormat`], such as `"1e300"` with
+    /// [`FloatFormat::Scientific`] or [`FloatFormat::Auto`].
+    #[must_use]
+    pub const fn with_floats(mut self, floats: FloatFormat) -> Self {
+        self.format.floats = floats;
+        self
+    }
+
+    /// Returns the inner serializer.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    #[inline]
+    fn wrap<C>(&self, inner: C) -> Stringify<C> {
+        Stringify {
+            inner,
+            format: self.format,
+        }
+    }
+}
+
+/// Serialize a value (of any type) with a [`StringifySerializer`], so that
+/// every number and boolean in it is written as a string.
+///
+/// ```rust
+/// use serde::Serialize;
+/// use serde_this_or_that::stringify;
+///
+/// #[derive(Serialize)]
+/// struct Msg {
+///     #[serde(serialize_with = "stringify")]
+///     retries: u64,
+///     #[serde(serialize_with = "stringify")]
+///     scores: Vec<f64>,
+///     enabled: bool,
+/// }
+///
+/// let msg = Msg { retries: 3, scores: vec![1.5, 2.0], enabled: true };
+/// assert_eq!(
+///     serde_json::to_string(&msg).unwrap(),
+///     r#"{"retries":"3","scores":["1.5","2"],"enabled":true}"#
+/// );
+/// ```
+///
+/// # Errors
+/// Returns an error if the serializer does.
+///
+pub fn stringify<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: ?Sized + Serialize,
+    S: Serializer,
+{
+    value.serialize(StringifySerializer::new(serializer))
+}
+
+/// Defines `serialize_*` methods which write the value as a string.
+macro_rules! serialize_display {
+    ($($method:ident($ty:ty);)*) => {
+        $(
+            fn $method(self, v: $ty) -> Result<Self::Ok, Self::Error> {
+                self.inner.collect_str(&v)
+            }
+        )*
+    };
+}
+
+impl<S> Serializer for StringifySerializer<S>
+where
+    S: Serializer,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = Stringify<S::SerializeSeq>;
+    type SerializeTuple = Stringify<S::SerializeTuple>;
+    type SerializeTupleStruct = Stringify<S::SerializeTupleStruct>;
+    type SerializeTupleVariant = Stringify<S::SerializeTupleVariant>;
+    type SerializeMap = Stringify<S::SerializeMap>;
+    type SerializeStruct = Stringify<S::SerializeStruct>;
+    type SerializeStructVariant = Stringify<S::SerializeStructVariant>;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+        self.inner.serialize_str(self.format.bools[usize::from(v)])
+    }
+
+    serialize_display! {
+        serialize_i8(i8);
+        serialize_i16(i16);
+        serialize_i32(i32);
+        serialize_i64(i64);
+        serialize_i128(i128);
+        serialize_u8(u8);
+        serialize_u16(u16);
+        serialize_u32(u32);
+        serialize_u64(u64);
+        serialize_u128(u128);
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+        self.inner.collect_str(&Float(v, self.format.floats))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+        self.inner.collect_str(&Float(v, self.format.floats))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        self.inner.serialize_char(v)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        self.inner.serialize_str(v)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        self.inner.serialize_bytes(v)
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        self.inner.serialize_none()
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let value = self.wrap(value);
+        self.inner.serialize_some(&value)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        self.inner.serialize_unit()
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
+        self.inner.serialize_unit_struct(name)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.inner
+            .serialize_unit_variant(name, variant_index, variant)
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let value = self.wrap(value);
+        self.inner.serialize_newtype_struct(name, &value)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let value = self.wrap(value);
+        self.inner
+            .serialize_newtype_variant(name, variant_index, variant, &value)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        let format = self.format;
+        self.inner
+            .serialize_seq(len)
+            .map(|inner| Stringify { inner, format })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        let format = self.format;
+        self.inner
+            .serialize_tuple(len)
+            .map(|inner| Stringify { inner, format })
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        let format = self.format;
+        self.inner
+            .serialize_tuple_struct(name, len)
+            .map(|inner| Stringify { inner, format })
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        let format = self.format;
+        self.inner
+            .serialize_tuple_variant(name, variant_index, variant, len)
+            .map(|inner| Stringify { inner, format })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        let format = self.format;
+        self.inner
+            .serialize_map(len)
+            .map(|inner| Stringify { inner, format })
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        let format = self.format;
+        self.inner
+            .serialize_struct(name, len)
+            .map(|inner| Stringify { inner, format })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        let format = self.format;
+        self.inner
+            .serialize_struct_variant(name, variant_index, variant, len)
+            .map(|inner| Stringify { inner, format })
+    }
+
+    fn collect_str<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Display,
+    {
+        self.inner.collect_str(value)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+/// A float which is written in a [`FloatFormat`].
+struct Float<T>(T, FloatFormat);
+
+impl<T> Display for Float<T>
+where
+    T: Copy + Display + LowerExp + Into<f64>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Float(v, format) = *self;
+        let abs = v.into().abs();
+        let scientific = match format {
+            FloatFormat::Decimal => false,
+            FloatFormat::Scientific => true,
+            FloatFormat::Auto => abs >= 1e21 || (abs < 1e-6 && abs != 0.0),
+        };
+        if scientific {
+            LowerExp::fmt(&v, f)
+        } else {
+            Display::fmt(&v, f)
+        }
+    }
+}
+
+/// A value, or a compound serializer, whose values are written with a
+/// [`StringifySerializer`].
+pub struct Stringify<T> {
+    inner: T,
+    format: Format,
+}
+
+impl<T> Stringify<T> {
+    #[inline]
+    fn wrap<V>(&self, inner: V) -> Stringify<V> {
+        Stringify {
+            inner,
+            format: self.format,
+        }
+    }
+}
+
+impl<T> Serialize for Stringify<&T>
+where
+    T: ?Sized + Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.inner.serialize(StringifySerializer {
+            inner: serializer,
+            format: self.format,
+        })
+    }
+}
+
+/// Defines a compound serializer, whose `$method`s wrap each value (and
+/// key) in a [`Stringify`].
+macro_rules! impl_stringify_compound {
+    ($($trait:ident { $($method:ident($($arg:ident: $arg_ty:ty),*);)* })*) => {
+        $(
+            impl<S> $trait for Stringify<S>
+            where
+                S: $trait,
+            {
+                type Ok = S::Ok;
+                type Error = S::Error;
+
+                $(
+                    fn $method<T>(&mut self, $($arg: $arg_ty,)* value: &T) -> Result<(), Self::Error>
+                    where
+                        T: ?Sized + Serialize,
+                    {
+                        let value = self.wrap(value);
+                        self.inner.$method($($arg,)* &value)
+                    }
+                )*
+
+                fn end(self) -> Result<Self::Ok, Self::Error> {
+                    self.inner.end()
+                }
+            }
+        )*
+    };
+}
+
+impl_stringify_compound! {
+    SerializeSeq { serialize_element(); }
+    SerializeTuple { serialize_element(); }
+    SerializeTupleStruct { serialize_field(); }
+    SerializeTupleVariant { serialize_field(); }
+    SerializeMap { serialize_key(); serialize_value(); }
+    SerializeStruct { serialize_field(key: &'static str); }
+    SerializeStructVariant { serialize_field(key: &'static str); }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+    use std::collections::BTreeMap;
+
+    fn to_string<T: Serialize>(
+        value: &T,
+        true_str: &'static str,
+        false_str: &'static str,
+    ) -> String {
+        let mut out = Vec::new();
+        let mut ser = serde_json::Serializer::new(&mut out);
+        value
+            .serialize(StringifySerializer::new(&mut ser).with_bools(true_str, false_str))
+            .unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[derive(Serialize)]
+    struct Record {
+        id: u64,
+        offset: i32,
+        grade: f64,
+        active: bool,
+        name: &'static str,
+        initial: char,
+        limit: Option<u8>,
+        missing: Option<u8>,
+        tags: Vec<bool>,
+        counts: BTreeMap<u8, f32>,
+        pair: (i8, bool),
+        kind: Kind,
+        shape: Shape,
+        nested: Nested,
+    }
+
+    #[derive(Serialize)]
+    struct Nested(u128);
+
+    #[derive(Serialize)]
+    enum Kind {
+        Large,
+    }
+
+    #[derive(Serialize)]
+    enum Shape {
+        Rect { w: u64, h: u64 },
+    }
+
+    #[test]
+    fn test_stringify_document() {
+        let record = Record {
+            id: 3,
+            offset: -7,
+            grade: 81.5,
+            active: true,
+            name: "Jane",
+            initial: 'J',
+            limit: Some(10),
+            missing: None,
+            tags: vec![true, false],
+            counts: BTreeMap::from([(1, 0.25)]),
+            pair: (-1, false),
+            kind: Kind::Large,
+            shape: Shape::Rect { w: 2, h: 3 },
+            nested: Nested(u128::MAX),
+        };
+
+        assert_eq!(
+            to_string(&record, "1", "0"),
+            concat!(
+                r#"{"id":"3","offset":"-7","grade":"81.5","active":"1","name":"Jane","#,
+                r#""initial":"J","limit":"10","missing":null,"tags":["1","0"],"#,
+                r#""counts":{"1":"0.25"},"pair":["-1","0"],"kind":"Large","#,
+                r#""shape":{"Rect":{"w":"2","h":"3"}},"#,
+                r#""nested":"340282366920938463463374607431768211455"}"#,
+            )
+        );
+    }
+
+    #[test]
+    fn test_stringify_bools() {
+        assert_eq!(
+            to_string(&[true, false], "true", "false"),
+            r#"["true","false"]"#
+        );
+        assert_eq!(to_string(&[true, false], "Y", "N"), r#"["Y","N"]"#);
+    }
+
+    #[test]
+    fn test_stringify_floats() {
+        fn to_string(floats: FloatFormat) -> String {
+            let value = (1.5, 0.0, 1e300, -1e-7, 2.5e21_f32, Some(vec![1e-6]));
+            let mut out = Vec::new();
+            let mut ser = serde_json::Serializer::new(&mut out);
+            value
+                .serialize(StringifySerializer::new(&mut ser).with_floats(floats))
+                .unwrap();
+            String::from_utf8(out).unwrap()
+        }
+
+        let decimal = to_string(FloatFormat::Decimal);
+        assert!(
+            decimal.starts_with(r#"["1.5","0","1000000000"#),
+            "{decimal}"
+        );
+        assert_eq!(
+            to_string(FloatFormat::Scientific),
+            r#"["1.5e0","0e0","1e300","-1e-7","2.5e21",["1e-6"]]"#
+        );
+        assert_eq!(
+            to_string(FloatFormat::Auto),
+            r#"["1.5","0","1e300","-1e-7","2.5e21",["0.000001"]]"#
+        );
+    }
+
+    #[test]
+    fn test_stringify_round_trip() {
+        use crate::{as_bool, as_f64, as_u64};
+        use serde::Deserialize;
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Msg {
+            #[serde(serialize_with = "stringify", deserialize_with = "as_u64")]
+            count: u64,
+            #[serde(serialize_with = "stringify", deserialize_with = "as_f64")]
+            ratio: f64,
+            #[serde(serialize_with = "stringify", deserialize_with = "as_bool")]
+            active: bool,
+        }
+
+        let msg = Msg {
+            count: 3,
+            ratio: 0.5,
+            active: false,
+        };
+        let json = serde_json::to_string(&msg).unwrap();
+        assert_eq!(json, r#"{"count":"3","ratio":"0.5","active":"false"}"#);
+        assert_eq!(serde_json::from_str::<Msg>(&json).unwrap(), msg);
+    }
+}
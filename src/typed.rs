@@ -0,0 +1,34 @@
+//! Support for formats which are not self-describing, such as `bincode` and
+//! `postcard`, with the `typed` feature.
+//!
+//! Such a format can't tell a visitor which type of value comes next, so it
+//! doesn't support `deserialize_any`; the type has to be requested instead,
+//! such as with `deserialize_u64`. A self-describing binary format, such as
+//! MessagePack or CBOR, is not human-readable either, but (like JSON) it can
+//! hold a string where a number is expected, so the helpers only request the
+//! type when the `typed` feature is enabled.
+
+use crate::de::Deserializer;
+use crate::ser::Serializer;
+
+/// Returns `true` if a helper should request the concrete type from the
+/// `deserializer`, rather than call `deserialize_any`: that is, with the
+/// `typed` feature, for a format which is not human-readable.
+#[inline]
+pub fn is_typed<'de, D>(deserializer: &D) -> bool
+where
+    D: Deserializer<'de>,
+{
+    cfg!(feature = "typed") && !deserializer.is_human_readable()
+}
+
+/// Returns `true` if a value should be written for a format in which
+/// [`is_typed`] is `true`: that is, with the `typed` feature, for a format
+/// which is not human-readable.
+#[inline]
+pub(crate) fn is_typed_serializer<S>(serializer: &S) -> bool
+where
+    S: Serializer,
+{
+    cfg!(feature = "typed") && !serializer.is_human_readable()
+}
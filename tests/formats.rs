@@ -0,0 +1,581 @@
+//! Tests for the helper functions, and the types built on them, with
+//! formats other than JSON.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize, Serializer};
+#[cfg(feature = "serde_json")]
+use serde_this_or_that::as_json_str;
+use serde_this_or_that::{
+    as_bool, as_bytes, as_char, as_cow_str, as_f64, as_i64, as_map_keys, as_map_of_u64,
+    as_one_or_many, as_opt_bool, as_opt_bytes, as_opt_f64, as_opt_i64, as_opt_raw_string,
+    as_opt_string, as_opt_string_with, as_opt_u64, as_patch, as_raw_string, as_string,
+    as_string_with, as_u64, Coerced, Lenient, LenientOpt, Patch, Repr, StringMode,
+};
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+struct Record<'a> {
+    #[serde(deserialize_with = "as_bool")]
+    active: bool,
+    #[serde(deserialize_with = "as_opt_bool")]
+    verified: Option<bool>,
+    #[serde(deserialize_with = "as_u64")]
+    count: u64,
+    #[serde(deserialize_with = "as_opt_u64")]
+    limit: Option<u64>,
+    #[serde(deserialize_with = "as_i64")]
+    offset: i64,
+    #[serde(deserialize_with = "as_opt_i64")]
+    delta: Option<i64>,
+    #[serde(deserialize_with = "as_f64")]
+    grade: f64,
+    #[serde(deserialize_with = "as_opt_f64")]
+    ratio: Option<f64>,
+    #[serde(deserialize_with = "as_char")]
+    initial: char,
+    #[serde(deserialize_with = "as_string")]
+    name: String,
+    #[serde(deserialize_with = "as_opt_string")]
+    nickname: Option<String>,
+    #[serde(borrow, deserialize_with = "as_cow_str")]
+    label: std::borrow::Cow<'a, str>,
+    #[serde(deserialize_with = "as_map_keys")]
+    by_id: BTreeMap<u64, String>,
+    #[serde(deserialize_with = "as_map_of_u64")]
+    totals: BTreeMap<String, u64>,
+    small: Vec<Lenient<u8>>,
+    maybe_small: LenientOpt<i16>,
+    maybe: LenientOpt<u64>,
+    coerced: Coerced<u64>,
+    patch: Patch<String>,
+    #[serde(
+        default,
+        deserialize_with = "as_patch",
+        serialize_with = "serialize_patch"
+    )]
+    age: Option<Option<u64>>,
+    #[serde(deserialize_with = "as_bytes")]
+    payload: Vec<u8>,
+    #[serde(deserialize_with = "as_opt_bytes")]
+    maybe_payload: Option<Vec<u8>>,
+    #[serde(deserialize_with = "as_one_or_many")]
+    tags: Vec<String>,
+    #[cfg(feature = "serde_json")]
+    #[serde(deserialize_with = "as_json_str")]
+    metadata: BTreeMap<String, u64>,
+}
+
+/// Writes a field for [`as_patch`] in the same way as a [`Patch`].
+fn serialize_patch<S>(value: &Option<Option<u64>>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match value {
+        Some(Some(v)) => serializer.serialize_some(v),
+        _ => serializer.serialize_none(),
+    }
+}
+
+fn record() -> Record<'static> {
+    Record {
+        active: true,
+        verified: None,
+        count: 3,
+        limit: Some(10),
+        offset: -7,
+        delta: None,
+        grade: 81.5,
+        ratio: Some(0.25),
+        initial: 'J',
+        name: "Jane".into(),
+        nickname: Some("JJ".into()),
+        label: "label".into(),
+        by_id: BTreeMap::from([(1, "a".into()), (2, "b".into())]),
+        totals: BTreeMap::from([("x".into(), 1)]),
+        small: vec![Lenient(1), Lenient(255)],
+        maybe_small: LenientOpt(Some(-300)),
+        maybe: LenientOpt(None),
+        coerced: Coerced::with_repr(5, Repr::Str),
+        patch: Patch::Value("p".into()),
+        age: Some(Some(42)),
+        payload: vec![0xca, 0xfe],
+        maybe_payload: None,
+        tags: vec!["a".into()],
+        #[cfg(feature = "serde_json")]
+        metadata: BTreeMap::from([("a".into(), 1)]),
+    }
+}
+
+// A binary format which is not self-describing, such as `bincode`, needs
+// the `typed` feature.
+
+#[cfg(feature = "typed")]
+#[test]
+fn test_bincode_round_trip() {
+    let record = record();
+    let bytes = bincode::serialize(&record).unwrap();
+    let deserialized: Record<'_> = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(deserialized, record);
+}
+
+#[cfg(feature = "typed")]
+#[test]
+fn test_postcard_round_trip() {
+    let record = record();
+    let bytes = postcard::to_allocvec(&record).unwrap();
+    let deserialized: Record<'_> = postcard::from_bytes(&bytes).unwrap();
+    assert_eq!(deserialized, record);
+}
+
+#[cfg(feature = "typed")]
+#[test]
+fn test_binary_patch_null() {
+    let mut record = record();
+    record.patch = Patch::Null;
+
+    let bytes = bincode::serialize(&record).unwrap();
+    let deserialized: Record<'_> = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(deserialized.patch, Patch::Null);
+
+    let bytes = postcard::to_allocvec(&record).unwrap();
+    let deserialized: Record<'_> = postcard::from_bytes(&bytes).unwrap();
+    assert_eq!(deserialized.patch, Patch::Null);
+}
+
+#[test]
+fn test_json_is_still_lenient() {
+    let json = r#"{
+        "active": "yes", "verified": "maybe", "count": "3", "limit": 10.0,
+        "offset": "-7", "delta": "", "grade": "81.5", "ratio": 0.25,
+        "initial": "J", "name": "Jane", "nickname": "JJ", "label": "label",
+        "by_id": {"1.0": "a", "2": "b"}, "totals": {"x": "1"},
+        "small": ["1", 255], "maybe_small": "-300", "maybe": "x",
+        "coerced": "5", "patch": "p", "age": "42", "payload": "cafe",
+        "maybe_payload": "", "tags": "a", "metadata": "{\"a\": 1}"
+    }"#;
+    let deserialized: Record<'_> = serde_json::from_str(json).unwrap();
+    assert_eq!(deserialized, record());
+}
+
+/// The target for the tests below, which each read the same values (in a
+/// variety of shapes) from a different format.
+#[derive(Debug, PartialEq, Deserialize)]
+struct Settings {
+    #[serde(deserialize_with = "as_u64")]
+    retries: u64,
+    #[serde(default, deserialize_with = "as_opt_u64")]
+    limit: Option<u64>,
+    #[serde(default, deserialize_with = "as_opt_f64")]
+    ratio: Option<f64>,
+    #[serde(deserialize_with = "as_bool")]
+    enabled: bool,
+    #[serde(default, deserialize_with = "as_opt_string")]
+    label: Option<String>,
+}
+
+fn settings() -> Settings {
+    Settings {
+        retries: 3,
+        limit: Some(10),
+        ratio: None,
+        enabled: true,
+        label: Some("7".into()),
+    }
+}
+
+/// A source for the binary formats, which (unlike the text formats) can't
+/// be written by hand.
+#[derive(Serialize)]
+struct Source<R, L, E, B> {
+    retries: R,
+    limit: Option<L>,
+    ratio: Option<f64>,
+    enabled: E,
+    label: Option<B>,
+}
+
+#[derive(Serialize)]
+struct Wrapper<T>(T);
+
+#[test]
+fn test_ron() {
+    let ron = r#"(
+        retries: "3",
+        limit: Some("10"),
+        ratio: None,
+        enabled: "yes",
+        label: Some(7),
+    )"#;
+    assert_eq!(ron::from_str::<Settings>(ron).unwrap(), settings());
+
+    // Nested options are unwrapped.
+    let ron = r#"(retries: Some(Some(3.0)), limit: 10, enabled: Some(true), label: "7")"#;
+    assert_eq!(ron::from_str::<Settings>(ron).unwrap(), settings());
+}
+
+#[test]
+fn test_yaml() {
+    let yaml = "retries: '3'\nlimit: 10.0\nratio: ~\nenabled: yes\nlabel: 7\n";
+    assert_eq!(serde_yaml::from_str::<Settings>(yaml).unwrap(), settings());
+}
+
+#[test]
+fn test_toml() {
+    let toml = "retries = \"3\"\nlimit = 10.0\nenabled = \"on\"\nlabel = 7\n";
+    assert_eq!(toml::from_str::<Settings>(toml).unwrap(), settings());
+}
+
+#[cfg(not(feature = "typed"))]
+#[test]
+fn test_message_pack() {
+    // MessagePack is self-describing, so (without the `typed` feature) the
+    // values are coerced just as with JSON.
+    let source = Source {
+        retries: Wrapper("3"),
+        limit: Some("10"),
+        ratio: None,
+        enabled: "yes",
+        label: Some(7),
+    };
+
+    let bytes = rmp_serde::to_vec_named(&source).unwrap();
+    assert_eq!(
+        rmp_serde::from_slice::<Settings>(&bytes).unwrap(),
+        settings()
+    );
+
+    let bytes = rmp_serde::to_vec(&source).unwrap();
+    assert_eq!(
+        rmp_serde::from_slice::<Settings>(&bytes).unwrap(),
+        settings()
+    );
+}
+
+#[test]
+fn test_message_pack_human_readable() {
+    let source = Source {
+        retries: Wrapper("3"),
+        limit: Some("10"),
+        ratio: None,
+        enabled: "yes",
+        label: Some(7),
+    };
+
+    let bytes = rmp_serde::to_vec_named(&source).unwrap();
+    let mut deserializer = rmp_serde::Deserializer::new(&bytes[..]).with_human_readable();
+    assert_eq!(
+        Settings::deserialize(&mut deserializer).unwrap(),
+        settings()
+    );
+}
+
+#[cfg(feature = "typed")]
+#[test]
+fn test_message_pack_typed() {
+    // With the `typed` feature, MessagePack (which is not human-readable)
+    // only accepts the native type.
+    let source = Source {
+        retries: Wrapper(3_u64),
+        limit: Some(10_u64),
+        ratio: None,
+        enabled: true,
+        label: Some("7"),
+    };
+
+    let bytes = rmp_serde::to_vec_named(&source).unwrap();
+    assert_eq!(
+        rmp_serde::from_slice::<Settings>(&bytes).unwrap(),
+        settings()
+    );
+}
+
+#[cfg(not(feature = "typed"))]
+#[test]
+fn test_cbor() {
+    // CBOR is self-describing, so (without the `typed` feature) the values
+    // are coerced just as with JSON.
+    let source = Source {
+        retries: Wrapper("3"),
+        limit: Some("10"),
+        ratio: None,
+        enabled: "yes",
+        label: Some(7),
+    };
+
+    let mut bytes = Vec::new();
+    ciborium::into_writer(&source, &mut bytes).unwrap();
+    assert_eq!(
+        ciborium::from_reader::<Settings, _>(&bytes[..]).unwrap(),
+        settings()
+    );
+}
+
+#[cfg(feature = "typed")]
+#[test]
+fn test_cbor_typed() {
+    // With the `typed` feature, CBOR (which is not human-readable) only
+    // accepts the native type.
+    let source = Source {
+        retries: Wrapper(3_u64),
+        limit: Some(10_u64),
+        ratio: None,
+        enabled: true,
+        label: Some("7"),
+    };
+
+    let mut bytes = Vec::new();
+    ciborium::into_writer(&source, &mut bytes).unwrap();
+    assert_eq!(
+        ciborium::from_reader::<Settings, _>(&bytes[..]).unwrap(),
+        settings()
+    );
+}
+
+/// A row of a CSV file, where every value is text, and `csv` infers a type
+/// for each cell when it is de-serialized with `deserialize_any`.
+#[derive(Debug, PartialEq, Deserialize)]
+struct Row {
+    #[serde(deserialize_with = "as_raw_string")]
+    zip: String,
+    #[serde(deserialize_with = "as_string")]
+    inferred_zip: String,
+    #[serde(deserialize_with = "as_raw_string")]
+    price: String,
+    #[serde(deserialize_with = "as_opt_raw_string")]
+    note: Option<String>,
+    #[serde(deserialize_with = "as_cell")]
+    cell: String,
+    #[serde(deserialize_with = "as_opt_cell")]
+    maybe_cell: Option<String>,
+    #[serde(deserialize_with = "as_bool")]
+    active: bool,
+    #[serde(deserialize_with = "as_opt_bool")]
+    verified: Option<bool>,
+    #[serde(deserialize_with = "as_u64")]
+    count: u64,
+    #[serde(deserialize_with = "as_opt_u64")]
+    limit: Option<u64>,
+    #[serde(deserialize_with = "as_i64")]
+    offset: i64,
+    #[serde(deserialize_with = "as_f64")]
+    grade: f64,
+    #[serde(deserialize_with = "as_char")]
+    initial: char,
+    #[serde(deserialize_with = "as_string")]
+    name: String,
+    #[serde(deserialize_with = "as_opt_string")]
+    nickname: Option<String>,
+    small: Lenient<u8>,
+    maybe: LenientOpt<u64>,
+    coerced: Coerced<u64>,
+}
+
+fn as_cell<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    as_string_with(deserializer, StringMode::Raw)
+}
+
+fn as_opt_cell<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    as_opt_string_with(deserializer, StringMode::Raw)
+}
+
+#[test]
+fn test_csv() {
+    let data = "\
+zip,inferred_zip,price,note,cell,maybe_cell,active,verified,count,limit,offset,grade,initial,name,nickname,small,maybe,coerced
+02134,02134,1.50,\"Hello, \"\"world\"\"\",1.50,007,yes,,3,10.0,-7,81.5, J ,\"Doe, Jane\",JJ,255,,5
+90210,90210,2,,,,0,false,,,\"-1\",,,Smith,7,\"1.0\",x,\"\"
+";
+    let rows = csv::Reader::from_reader(data.as_bytes())
+        .deserialize()
+        .collect::<Result<Vec<Row>, _>>()
+        .unwrap();
+
+    assert_eq!(
+        rows,
+        [
+            Row {
+                zip: "02134".into(),
+                inferred_zip: "2134".into(),
+                price: "1.50".into(),
+                note: Some("Hello, \"world\"".into()),
+                cell: "1.50".into(),
+                maybe_cell: Some("007".into()),
+                active: true,
+                verified: None,
+                count: 3,
+                limit: Some(10),
+                offset: -7,
+                grade: 81.5,
+                initial: 'J',
+                name: "Doe, Jane".into(),
+                nickname: Some("JJ".into()),
+                small: Lenient(255),
+                maybe: LenientOpt(None),
+                coerced: Coerced::with_repr(5, Repr::U64),
+            },
+            Row {
+                zip: "90210".into(),
+                inferred_zip: "90210".into(),
+                price: "2".into(),
+                note: None,
+                cell: String::new(),
+                maybe_cell: None,
+                active: false,
+                verified: Some(false),
+                count: 0,
+                limit: None,
+                offset: -1,
+                grade: 0.0,
+                initial: '\0',
+                name: "Smith".into(),
+                nickname: Some("7".into()),
+                small: Lenient(1),
+                maybe: LenientOpt(None),
+                coerced: Coerced::with_repr(0, Repr::EmptyStr),
+            },
+        ]
+    );
+}
+
+/// A deserializer which presents the value of the inner deserializer as a
+/// newtype struct, as some formats do for tagged or wrapped values.
+struct Newtype<D>(D);
+
+impl<'de, D: serde::Deserializer<'de>> serde::Deserializer<'de> for Newtype<D> {
+    type Error = D::Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, D::Error>
+    where
+        V: serde::de::Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self.0)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+#[test]
+fn test_newtype_struct() {
+    let mut deserializer = serde_json::Deserializer::from_str(r#""3""#);
+    assert_eq!(as_u64(Newtype(&mut deserializer)).unwrap(), 3);
+
+    let mut deserializer = serde_json::Deserializer::from_str("null");
+    assert_eq!(as_opt_bool(Newtype(&mut deserializer)).unwrap(), None);
+
+    let mut deserializer = serde_json::Deserializer::from_str(r#""7""#);
+    let value: Lenient<char> = Deserialize::deserialize(Newtype(&mut deserializer)).unwrap();
+    assert_eq!(value, Lenient('7'));
+}
+
+#[cfg(feature = "xml")]
+mod xml {
+    use super::*;
+
+    /// An element of an XML document, where every value is text, and each
+    /// child element is handed over as a map (of its attributes and text).
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Item {
+        #[serde(rename = "@id", deserialize_with = "as_u64")]
+        id: u64,
+        #[serde(deserialize_with = "as_bool")]
+        active: bool,
+        #[serde(default, deserialize_with = "as_opt_bool")]
+        verified: Option<bool>,
+        #[serde(deserialize_with = "as_u64")]
+        count: u64,
+        #[serde(default, deserialize_with = "as_opt_u64")]
+        limit: Option<u64>,
+        #[serde(deserialize_with = "as_f64")]
+        grade: f64,
+        #[serde(default, deserialize_with = "as_opt_f64")]
+        ratio: Option<f64>,
+        #[serde(deserialize_with = "as_string")]
+        name: String,
+        #[serde(default, deserialize_with = "as_opt_string")]
+        nickname: Option<String>,
+    }
+
+    #[test]
+    fn test_quick_xml() {
+        let xml = r#"
+            <item id="1">
+                <active>yes</active>
+                <verified>false</verified>
+                <count unit="tries">3</count>
+                <limit>10.0</limit>
+                <grade>81.5</grade>
+                <ratio>0.25</ratio>
+                <name lang="en">Jane</name>
+                <nickname>JJ</nickname>
+            </item>"#;
+        assert_eq!(
+            quick_xml::de::from_str::<Item>(xml).unwrap(),
+            Item {
+                id: 1,
+                active: true,
+                verified: Some(false),
+                count: 3,
+                limit: Some(10),
+                grade: 81.5,
+                ratio: Some(0.25),
+                name: "Jane".into(),
+                nickname: Some("JJ".into()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_quick_xml_empty_elements() {
+        let xml = r#"
+            <item id="2">
+                <active/>
+                <verified></verified>
+                <count></count>
+                <limit unit="tries"/>
+                <grade/>
+                <ratio>x</ratio>
+                <name></name>
+                <nickname/>
+            </item>"#;
+        assert_eq!(
+            quick_xml::de::from_str::<Item>(xml).unwrap(),
+            Item {
+                id: 2,
+                active: false,
+                verified: None,
+                count: 0,
+                limit: None,
+                grade: 0.0,
+                ratio: None,
+                name: String::new(),
+                nickname: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_quick_xml_missing_elements() {
+        let xml = r#"<item id="3"><active>1</active><count>4</count><grade>2</grade><name>7</name></item>"#;
+        let item = quick_xml::de::from_str::<Item>(xml).unwrap();
+        assert_eq!((item.active, item.count, item.grade), (true, 4, 2.0));
+        assert_eq!((item.verified, item.limit, item.ratio), (None, None, None));
+        assert_eq!(item.name, "7");
+    }
+
+    #[test]
+    fn test_quick_xml_nested_element_is_an_error() {
+        let xml = r#"<item id="4"><active>yes</active><count><n>3</n></count><grade>1</grade><name>x</name></item>"#;
+        let err = quick_xml::de::from_str::<Item>(xml).unwrap_err();
+        assert!(err.to_string().contains("invalid type: map"), "{err}");
+    }
+}